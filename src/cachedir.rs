@@ -0,0 +1,60 @@
+use std::{fs, io::Read, path::Path};
+
+/// What a `CACHEDIR.TAG` has to start with, see <https://bford.info/cachedir/>.
+pub(crate) const SIGNATURE: &str = "Signature: 8a477f597d28d172789f06886806bc55";
+
+/// How much of a tag is read, the signature and a few comment lines.
+const TAG_LEN: u64 = 1024;
+
+/// `None` unless `dir` has a `CACHEDIR.TAG` with the signature, otherwise the
+/// tool its comment says created it, eg: `# ... cache directory tag created by ccache.`
+pub(crate) fn tag(dir: &Path) -> Option<Option<String>> {
+    let mut content = Vec::new();
+    fs::File::open(dir.join("CACHEDIR.TAG"))
+        .ok()?
+        .take(TAG_LEN)
+        .read_to_end(&mut content)
+        .ok()?;
+    let content = String::from_utf8_lossy(&content);
+    if !content.starts_with(SIGNATURE) {
+        return None;
+    }
+    Some(content.lines().skip(1).find_map(|line| {
+        let (_, tool) = line.strip_prefix('#')?.split_once("created by ")?;
+        let tool = tool.trim().trim_end_matches('.');
+        Some(tool.to_string()).filter(|tool| !tool.is_empty())
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+
+    #[test]
+    fn reads_the_tool_of_a_tag() {
+        let dir = TempDir::new("cachedir-tag");
+        dir.tag("cargo", "cargo")
+            .file(
+                "plain/CACHEDIR.TAG",
+                &format!(
+                    "{SIGNATURE}\n# a cache directory tag, see https://bford.info/cachedir/\n"
+                ),
+            )
+            .file(
+                "empty-tool/CACHEDIR.TAG",
+                &format!("{SIGNATURE}\n# created by .\n"),
+            )
+            .file(
+                "bad/CACHEDIR.TAG",
+                "Signature: 0000\n# created by nobody.\n",
+            )
+            .dirs(&["none"]);
+        let tag = |rel: &str| tag(&dir.path().join(rel));
+        assert_eq!(tag("cargo"), Some(Some("cargo".to_string())));
+        assert_eq!(tag("plain"), Some(None));
+        assert_eq!(tag("empty-tool"), Some(None));
+        assert_eq!(tag("bad"), None);
+        assert_eq!(tag("none"), None);
+    }
+}
@@ -0,0 +1,453 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use glob::Pattern;
+use once_cell::sync::OnceCell;
+use serde::Deserialize;
+
+use crate::{cachedir, paths, scan_category::ScanCate};
+
+mod sweep;
+
+#[derive(Debug, Default, Deserialize)]
+struct Manifest {
+    workspace: Option<Workspace>,
+    package: Option<Package>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Workspace {
+    members: Vec<String>,
+    exclude: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Package {
+    /// `package.workspace`, an explicit path to the workspace root
+    workspace: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Config {
+    #[serde(default)]
+    build: Build,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Build {
+    #[serde(rename = "target-dir")]
+    target_dir: Option<PathBuf>,
+}
+
+/// What `target_dir` takes from the environment, read once.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct BuildEnv {
+    /// `CARGO_TARGET_DIR` or `CARGO_BUILD_TARGET_DIR`, made absolute against the current dir
+    pub(crate) target_dir: Option<PathBuf>,
+    /// `$CARGO_HOME` or `~/.cargo`, whose config is read after the ones up the tree
+    pub(crate) cargo_home: Option<PathBuf>,
+}
+
+impl BuildEnv {
+    fn current() -> Self {
+        let target_dir = ["CARGO_TARGET_DIR", "CARGO_BUILD_TARGET_DIR"]
+            .into_iter()
+            .find_map(|var| env::var_os(var).filter(|dir| !dir.is_empty()))
+            .map(|dir| match env::current_dir() {
+                Ok(cwd) => cwd.join(dir),
+                Err(_) => PathBuf::from(dir),
+            });
+        let cargo_home = env::var_os("CARGO_HOME")
+            .map(PathBuf::from)
+            .or_else(|| paths::home_dir().map(|home| home.join(".cargo")));
+        Self {
+            target_dir,
+            cargo_home,
+        }
+    }
+}
+
+static BUILD_ENV: OnceCell<BuildEnv> = OnceCell::new();
+
+/// Use `env` instead of the process environment, before anything looked it up.
+#[cfg(test)]
+pub(crate) fn init_env(env: BuildEnv) -> Result<(), BuildEnv> {
+    BUILD_ENV.set(env)
+}
+
+fn build_env() -> &'static BuildEnv {
+    BUILD_ENV.get_or_init(BuildEnv::current)
+}
+
+fn read_manifest(dir: &Path) -> Option<Manifest> {
+    let content = fs::read_to_string(dir.join("Cargo.toml")).ok()?;
+    toml::from_str(&content).ok()
+}
+
+/// The workspace `dir` belongs to, `dir` itself for a workspace root or a lone package.
+pub(crate) fn workspace_root(dir: &Path) -> PathBuf {
+    let Some(manifest) = read_manifest(dir) else {
+        return dir.to_path_buf();
+    };
+    if manifest.workspace.is_some() {
+        return dir.to_path_buf();
+    }
+    if let Some(root) = manifest.package.and_then(|package| package.workspace) {
+        return dir
+            .join(root)
+            .canonicalize()
+            .unwrap_or_else(|_| dir.to_path_buf());
+    }
+    // like Cargo, the closest ancestor with a `[workspace]` decides
+    for root in dir.ancestors().skip(1) {
+        if let Some(workspace) = read_manifest(root).and_then(|manifest| manifest.workspace) {
+            let Ok(rel) = dir.strip_prefix(root) else {
+                break;
+            };
+            let listed = |globs: &[String]| {
+                globs.iter().any(|glob| {
+                    Pattern::new(glob.trim_end_matches('/')).is_ok_and(|p| p.matches_path(rel))
+                })
+            };
+            if listed(&workspace.members) && !listed(&workspace.exclude) {
+                return root.to_path_buf();
+            }
+            break;
+        }
+    }
+    dir.to_path_buf()
+}
+
+/// Where Cargo puts the build output of the workspace at `root`, in Cargo's order:
+/// `CARGO_TARGET_DIR`, `build.target-dir` of the closest `.cargo/config.toml`
+/// up the tree or in `$CARGO_HOME`, then `<root>/target`.
+pub(crate) fn target_dir(root: &Path, env: &BuildEnv) -> PathBuf {
+    if let Some(dir) = &env.target_dir {
+        return dir.clone();
+    }
+    let configs = root
+        .ancestors()
+        .map(|dir| dir.join(".cargo"))
+        .chain(env.cargo_home.clone());
+    for dot_cargo in configs {
+        for name in ["config.toml", "config"] {
+            let Ok(content) = fs::read_to_string(dot_cargo.join(name)) else {
+                continue;
+            };
+            if let Some(dir) = toml::from_str::<Config>(&content)
+                .ok()
+                .and_then(|config| config.build.target_dir)
+            {
+                // relative to the directory holding `.cargo`
+                let base = dot_cargo.parent().unwrap_or(&dot_cargo);
+                return base.join(dir);
+            }
+            break;
+        }
+    }
+    root.join("target")
+}
+
+/// The built target directory of `dir` if it is a workspace root and `cate` owns it,
+/// members share the one of their workspace.
+pub(crate) fn built_target_dir(dir: &Path, cate: &ScanCate) -> Option<PathBuf> {
+    if workspace_root(dir) != dir {
+        return None;
+    }
+    target_dir(dir, build_env())
+        .canonicalize()
+        .ok()
+        .filter(|target| target.is_dir() && !dir.starts_with(target) && cate.owns(target))
+}
+
+/// Whether `dir` is where a Cargo project builds into, told from `dir` alone:
+/// the target dir of a workspace above it, or one Cargo tagged wherever its workspace is.
+pub(crate) fn is_target_dir(dir: &Path, cate: &ScanCate) -> bool {
+    let Ok(dir) = dir.canonicalize() else {
+        return false;
+    };
+    matches!(cachedir::tag(&dir), Some(Some(tool)) if tool == "cargo")
+        || dir.ancestors().skip(1).any(|root| {
+            cate.access_keyfile(root) && built_target_dir(root, cate).as_deref() == Some(&dir)
+        })
+}
+
+/// The parts of the target directory of `dir` that `clean` selects.
+pub(crate) fn artifacts(dir: &Path, cate: &ScanCate, clean: &Clean) -> Vec<PathBuf> {
+    built_target_dir(dir, cate).map_or_else(Vec::new, |target| clean.select(&target))
+}
+
+/// Which part of a target directory is removed.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub(crate) enum Clean {
+    /// the whole target directory
+    #[default]
+    All,
+    /// `debug/incremental`, the cheapest to rebuild
+    Incremental,
+    /// the `debug` profile
+    Debug,
+    /// `doc`, output of `cargo doc`
+    Doc,
+    /// everything that is not a `release` build
+    ExceptRelease,
+    /// the output of one cross compilation target, eg: `wasm32-unknown-unknown`
+    Triple(String),
+    /// the units built by toolchains rustup no longer has,
+    /// and with days the ones not rebuilt for that long
+    Sweep(Option<u64>),
+}
+
+impl FromStr for Clean {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "all" => Clean::All,
+            "incremental" => Clean::Incremental,
+            "debug" => Clean::Debug,
+            "doc" => Clean::Doc,
+            "except-release" => Clean::ExceptRelease,
+            "sweep" => Clean::Sweep(None),
+            "release" => {
+                return Err("release is kept by every part, use all to remove it".to_string())
+            }
+            triple
+                if !triple.is_empty()
+                    && !triple.starts_with('.')
+                    && !triple.contains(['/', '\\']) =>
+            {
+                Clean::Triple(triple.to_string())
+            }
+            _ => return Err(format!("not a part of a target directory: {s:?}")),
+        })
+    }
+}
+
+impl Clean {
+    /// The parts the detail view sizes, besides every target triple.
+    pub(crate) const PARTS: [Clean; 4] = [
+        Clean::Incremental,
+        Clean::Debug,
+        Clean::Doc,
+        Clean::ExceptRelease,
+    ];
+
+    /// The `--cargo` value that selects this part.
+    pub(crate) fn label(&self) -> String {
+        match self {
+            Clean::All => "all".to_string(),
+            Clean::Incremental => "incremental".to_string(),
+            Clean::Debug => "debug".to_string(),
+            Clean::Doc => "doc".to_string(),
+            Clean::ExceptRelease => "except-release".to_string(),
+            Clean::Triple(triple) => triple.clone(),
+            Clean::Sweep(_) => "sweep".to_string(),
+        }
+    }
+
+    /// The existing directories of `target` this part covers, a cross compilation
+    /// target has its own `debug`, `release` and `doc` under `target/<triple>`.
+    pub(crate) fn select(&self, target: &Path) -> Vec<PathBuf> {
+        let in_profiles = |rel: &str| {
+            [target.to_path_buf()]
+                .into_iter()
+                .chain(triples(target))
+                .map(|dir| dir.join(rel))
+                .filter(|dir| dir.is_dir())
+                .collect()
+        };
+        match self {
+            Clean::All => vec![target.to_path_buf()],
+            Clean::Incremental => in_profiles("debug/incremental"),
+            Clean::Debug => in_profiles("debug"),
+            Clean::Doc => in_profiles("doc"),
+            Clean::ExceptRelease => {
+                let triples = triples(target);
+                subdirectories(target)
+                    .into_iter()
+                    .filter(|dir| !dir.ends_with("release"))
+                    .flat_map(|dir| {
+                        if triples.contains(&dir) {
+                            subdirectories(&dir)
+                                .into_iter()
+                                .filter(|dir| !dir.ends_with("release"))
+                                .collect()
+                        } else {
+                            vec![dir]
+                        }
+                    })
+                    .collect()
+            }
+            // only a directory that holds profiles, never a profile or `..`
+            Clean::Triple(triple) => triples(target)
+                .into_iter()
+                .filter(|dir| dir.file_name().is_some_and(|name| name == triple.as_str()))
+                .collect(),
+            Clean::Sweep(days) => sweep::stale(target, *days)
+                .into_values()
+                .flatten()
+                .collect(),
+        }
+    }
+}
+
+/// What `--cargo sweep` would remove from `target`, by reason, eg: `sweep: rustc 1.80.0-nightly (…)`.
+/// Units are only old enough with the days of a `clean` sweep.
+pub(crate) fn sweep_parts(target: &Path, clean: &Clean) -> Vec<(String, Vec<PathBuf>)> {
+    let days = match clean {
+        Clean::Sweep(days) => *days,
+        _ => None,
+    };
+    sweep::stale(target, days)
+        .into_iter()
+        .map(|(reason, paths)| (reason.label(), paths))
+        .collect()
+}
+
+fn subdirectories(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|entry| entry.path())
+        .collect()
+}
+
+/// The `target/<triple>` directories, told from profiles by the profiles they hold.
+pub(crate) fn triples(target: &Path) -> Vec<PathBuf> {
+    subdirectories(target)
+        .into_iter()
+        .filter(|dir| {
+            !dir.ends_with("doc") && (dir.join("debug").is_dir() || dir.join("release").is_dir())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+
+    #[test]
+    fn parses_parts_and_triples() {
+        assert_eq!("incremental".parse(), Ok(Clean::Incremental));
+        assert_eq!("debug".parse(), Ok(Clean::Debug));
+        assert_eq!("except-release".parse(), Ok(Clean::ExceptRelease));
+        assert_eq!(
+            "wasm32-unknown-unknown".parse(),
+            Ok(Clean::Triple("wasm32-unknown-unknown".to_string()))
+        );
+    }
+
+    #[test]
+    fn rejects_what_is_not_a_triple() {
+        for s in [
+            "",
+            ".",
+            "..",
+            "release",
+            ".fingerprint",
+            "../x",
+            "a/b",
+            "a\\b",
+        ] {
+            assert!(s.parse::<Clean>().is_err(), "{s:?} parsed");
+        }
+    }
+
+    #[test]
+    fn selects_only_existing_triples() {
+        let dir = TempDir::new("select");
+        dir.dirs(&[
+            "debug/incremental",
+            "release",
+            "doc",
+            "wasm32-unknown-unknown/debug/incremental",
+            "wasm32-unknown-unknown/release",
+        ]);
+        let target = dir.path();
+
+        let triple = |name: &str| Clean::Triple(name.to_string()).select(target);
+        assert_eq!(
+            triple("wasm32-unknown-unknown"),
+            vec![target.join("wasm32-unknown-unknown")]
+        );
+        // a profile or a missing triple is never selected, even if it parsed
+        assert!(triple("debug").is_empty());
+        assert!(triple("doc").is_empty());
+        assert!(triple("aarch64-apple-darwin").is_empty());
+
+        let mut incremental = Clean::Incremental.select(target);
+        incremental.sort();
+        assert_eq!(
+            incremental,
+            vec![
+                target.join("debug/incremental"),
+                target.join("wasm32-unknown-unknown/debug/incremental"),
+            ]
+        );
+
+        let kept = Clean::ExceptRelease.select(target);
+        assert!(kept.iter().all(|dir| !dir.ends_with("release")));
+        assert!(kept.contains(&target.join("debug")));
+        assert!(kept.contains(&target.join("wasm32-unknown-unknown/debug")));
+    }
+
+    #[test]
+    fn finds_the_workspace_root() {
+        let dir = TempDir::new("workspace-root");
+        dir.file(
+            "ws/Cargo.toml",
+            "[workspace]\nmembers = [\"crates/*\"]\nexclude = [\"crates/old\"]",
+        )
+        .file("ws/crates/a/Cargo.toml", "[package]\nname = \"a\"")
+        .file("ws/crates/old/Cargo.toml", "[package]\nname = \"old\"")
+        .file("ws/tools/b/Cargo.toml", "[package]\nname = \"b\"")
+        .file(
+            "elsewhere/c/Cargo.toml",
+            "[package]\nname = \"c\"\nworkspace = \"../../ws\"",
+        );
+        let ws = dir.path().join("ws");
+        let root = |rel: &str| workspace_root(&dir.path().join(rel));
+        assert_eq!(root("ws"), ws);
+        assert_eq!(root("ws/crates/a"), ws);
+        assert_eq!(root("elsewhere/c"), ws);
+        // excluded or not listed, a package is its own root
+        assert_eq!(root("ws/crates/old"), ws.join("crates/old"));
+        assert_eq!(root("ws/tools/b"), ws.join("tools/b"));
+    }
+
+    #[test]
+    fn finds_the_target_dir() {
+        let dir = TempDir::new("target-dir");
+        dir.file("plain/Cargo.toml", "")
+            .file("conf/.cargo/config.toml", "[build]\ntarget-dir = \"out\"")
+            .file("conf/app/Cargo.toml", "")
+            .file("home/config.toml", "[build]\ntarget-dir = \"/shared\"");
+        let path = |rel: &str| dir.path().join(rel);
+        let none = BuildEnv::default();
+        assert_eq!(target_dir(&path("plain"), &none), path("plain/target"));
+        // relative to the directory holding `.cargo`, not to the workspace
+        assert_eq!(target_dir(&path("conf/app"), &none), path("conf/out"));
+
+        let home = BuildEnv {
+            cargo_home: Some(path("home")),
+            ..Default::default()
+        };
+        assert_eq!(target_dir(&path("plain"), &home), Path::new("/shared"));
+        assert_eq!(target_dir(&path("conf/app"), &home), path("conf/out"));
+
+        let var = BuildEnv {
+            target_dir: Some(path("var")),
+            ..home
+        };
+        assert_eq!(target_dir(&path("conf/app"), &var), path("var"));
+    }
+}
@@ -0,0 +1,304 @@
+use std::{
+    collections::{hash_map::Entry, BTreeMap, HashMap, HashSet},
+    env, fs,
+    io::Read,
+    path::{Path, PathBuf},
+    process::Command,
+    time::{Duration, SystemTime},
+};
+
+use once_cell::sync::OnceCell;
+use serde::Deserialize;
+
+use crate::paths;
+
+/// How far into an `.rmeta` or `.rlib` the `rustc 1.80.0 (…)` header is looked for.
+const HEADER_LEN: u64 = 4096;
+
+static INSTALLED: OnceCell<Option<HashSet<String>>> = OnceCell::new();
+
+/// Why a unit can go.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Stale {
+    /// built by a toolchain rustup no longer has, eg: `rustc 1.80.0-nightly (…)`
+    Uninstalled(String),
+    /// its fingerprint was not touched in the given days
+    Untouched(u64),
+}
+
+impl Stale {
+    pub(crate) fn label(&self) -> String {
+        match self {
+            Stale::Uninstalled(version) => format!("sweep: {version}"),
+            Stale::Untouched(days) => format!("sweep: untouched for {days} days"),
+        }
+    }
+}
+
+/// One compiled crate of a profile, `.fingerprint/<name>-<hash>` and what shares its hash.
+#[derive(Debug)]
+struct Unit {
+    /// `rustc` of the fingerprint, a hash of the compiler that built it
+    rustc: Option<u64>,
+    touched: SystemTime,
+    paths: Vec<PathBuf>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Fingerprint {
+    rustc: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct RustcInfo {
+    outputs: HashMap<String, RustcOutput>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RustcOutput {
+    stdout: String,
+}
+
+/// The versions of the toolchains rustup has installed, eg: `rustc 1.80.0 (051478957 2024-07-21)`,
+/// `None` without a rustup installation to compare against.
+fn installed() -> Option<&'static HashSet<String>> {
+    INSTALLED
+        .get_or_init(|| {
+            let rustup = env::var_os("RUSTUP_HOME")
+                .map(PathBuf::from)
+                .or_else(|| paths::home_dir().map(|home| home.join(".rustup")))?;
+            let toolchains = fs::read_dir(rustup.join("toolchains")).ok()?;
+            Some(
+                toolchains
+                    .flatten()
+                    .filter_map(|toolchain| {
+                        let output = Command::new(toolchain.path().join("bin").join("rustc"))
+                            .arg("-V")
+                            .output()
+                            .ok()?;
+                        let version = String::from_utf8(output.stdout).ok()?;
+                        Some(version.trim().to_string())
+                    })
+                    .collect(),
+            )
+        })
+        .as_ref()
+}
+
+/// The units of `target` that can go and why, grouped by reason.
+/// Units of an uninstalled toolchain go whatever their age,
+/// with `days` the ones not rebuilt for that long go too.
+pub(crate) fn stale(target: &Path, days: Option<u64>) -> BTreeMap<Stale, Vec<PathBuf>> {
+    let mut stale = BTreeMap::<Stale, Vec<PathBuf>>::new();
+    let cutoff = days
+        .and_then(|days| SystemTime::now().checked_sub(Duration::from_secs(days * 24 * 60 * 60)));
+    let last_used = last_used(target);
+
+    for profile in profiles(target) {
+        let units = units(&profile);
+        // every unit built by one compiler shares its `rustc` hash
+        let mut versions = HashMap::new();
+        for unit in &units {
+            if let Some(Entry::Vacant(entry)) = unit.rustc.map(|rustc| versions.entry(rustc)) {
+                if let Some(version) = unit_version(unit) {
+                    entry.insert(version);
+                }
+            }
+        }
+        // the newest unit was built by the compiler `.rustc_info.json` recorded last
+        if let (Some(version), Some(newest)) = (&last_used, units.iter().max_by_key(|u| u.touched))
+        {
+            if let Some(rustc) = newest.rustc {
+                versions.entry(rustc).or_insert_with(|| version.clone());
+            }
+        }
+
+        for unit in units {
+            let version = unit.rustc.and_then(|rustc| versions.get(&rustc));
+            let reason = match (installed(), version) {
+                (Some(installed), Some(version)) if !installed.contains(version) => {
+                    Stale::Uninstalled(version.clone())
+                }
+                _ if cutoff.is_some_and(|cutoff| unit.touched < cutoff) => {
+                    Stale::Untouched(days.unwrap_or_default())
+                }
+                _ => continue,
+            };
+            stale.entry(reason).or_default().extend(unit.paths);
+        }
+    }
+    stale
+}
+
+/// The directories of `target` with a `.fingerprint`, eg: `debug`, `wasm32-unknown-unknown/release`.
+fn profiles(target: &Path) -> Vec<PathBuf> {
+    let mut profiles = Vec::new();
+    let mut stack = vec![(target.to_path_buf(), 0)];
+    while let Some((dir, depth)) = stack.pop() {
+        if dir.join(".fingerprint").is_dir() {
+            profiles.push(dir);
+        } else if depth < 2 {
+            if let Ok(entries) = fs::read_dir(&dir) {
+                for entry in entries.flatten() {
+                    if entry.file_type().is_ok_and(|t| t.is_dir()) {
+                        stack.push((entry.path(), depth + 1));
+                    }
+                }
+            }
+        }
+    }
+    profiles
+}
+
+/// `name-hash` to `hash`, for fingerprints, build script dirs and `deps` files alike.
+fn unit_hash(file_name: &str) -> Option<&str> {
+    let stem = file_name.split('.').next()?;
+    let (_, hash) = stem.rsplit_once('-')?;
+    Some(hash).filter(|hash| hash.len() == 16 && hash.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+fn units(profile: &Path) -> Vec<Unit> {
+    let mut by_hash = HashMap::<String, Vec<PathBuf>>::new();
+    for dir in ["deps", "build"] {
+        let Ok(entries) = fs::read_dir(profile.join(dir)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if let Some(hash) = unit_hash(&entry.file_name().to_string_lossy()) {
+                by_hash
+                    .entry(hash.to_string())
+                    .or_default()
+                    .push(entry.path());
+            }
+        }
+    }
+
+    let Ok(fingerprints) = fs::read_dir(profile.join(".fingerprint")) else {
+        return Vec::new();
+    };
+    fingerprints
+        .flatten()
+        .filter_map(|entry| {
+            let hash = unit_hash(&entry.file_name().to_string_lossy())?.to_string();
+            let mut rustc = None;
+            let mut touched = SystemTime::UNIX_EPOCH;
+            for file in fs::read_dir(entry.path()).ok()?.flatten() {
+                if let Ok(modified) = file.metadata().and_then(|m| m.modified()) {
+                    touched = touched.max(modified);
+                }
+                if rustc.is_none() && file.path().extension().is_some_and(|ext| ext == "json") {
+                    rustc = fs::read_to_string(file.path())
+                        .ok()
+                        .and_then(|content| serde_json::from_str::<Fingerprint>(&content).ok())
+                        .map(|fingerprint| fingerprint.rustc);
+                }
+            }
+            let mut paths = by_hash.remove(&hash).unwrap_or_default();
+            paths.push(entry.path());
+            Some(Unit {
+                rustc,
+                touched,
+                paths,
+            })
+        })
+        .collect()
+}
+
+/// The compiler version in the header of the unit's `.rmeta` or `.rlib`, if it has one.
+fn unit_version(unit: &Unit) -> Option<String> {
+    unit.paths
+        .iter()
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext == "rmeta" || ext == "rlib")
+        })
+        .find_map(|path| {
+            let mut header = Vec::new();
+            fs::File::open(path)
+                .ok()?
+                .take(HEADER_LEN)
+                .read_to_end(&mut header)
+                .ok()?;
+            let header = String::from_utf8_lossy(&header);
+            let start = header.find("rustc ")?;
+            let end = start + header[start..].find(')')?;
+            Some(header[start..=end].to_string())
+        })
+}
+
+/// The compiler of the last build of `target`, from the `rustc -vV` output cached in `.rustc_info.json`.
+fn last_used(target: &Path) -> Option<String> {
+    let content = fs::read_to_string(target.join(".rustc_info.json")).ok()?;
+    let info = serde_json::from_str::<RustcInfo>(&content).ok()?;
+    info.outputs.into_values().find_map(|output| {
+        let line = output.stdout.lines().next()?.to_string();
+        Some(line).filter(|line| line.starts_with("rustc "))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+
+    #[test]
+    fn takes_the_hash_of_a_unit() {
+        for name in [
+            "serde-0123456789abcdef",
+            "libserde-0123456789abcdef.rlib",
+            "serde_json-0123456789abcdef.d",
+            "build-script-build-0123456789abcdef",
+        ] {
+            assert_eq!(unit_hash(name), Some("0123456789abcdef"), "{name}");
+        }
+        for name in [
+            "serde",
+            "serde-0123",
+            "serde-0123456789abcdefg",
+            "serde-0123456789abcdeg",
+            "libserde.rlib",
+        ] {
+            assert_eq!(unit_hash(name), None, "{name}");
+        }
+    }
+
+    #[test]
+    fn sweeps_units_not_touched_in_days() {
+        let dir = TempDir::new("sweep");
+        for name in ["old-0123456789abcdef", "new-fedcba9876543210"] {
+            let (crate_name, hash) = name.split_once('-').unwrap();
+            dir.file(
+                &format!("debug/.fingerprint/{name}/lib-{crate_name}.json"),
+                r#"{"rustc": 1}"#,
+            )
+            .file(&format!("debug/deps/lib{crate_name}-{hash}.rlib"), "")
+            .dirs(&[&format!("debug/build/{name}")]);
+        }
+        let fingerprint = dir
+            .path()
+            .join("debug/.fingerprint/old-0123456789abcdef/lib-old.json");
+        fs::File::options()
+            .write(true)
+            .open(fingerprint)
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(40 * 24 * 60 * 60))
+            .unwrap();
+
+        // without a compiler version in the rlib, only the age decides
+        assert!(stale(dir.path(), None).is_empty());
+        assert!(stale(dir.path(), Some(60)).is_empty());
+        let stale = stale(dir.path(), Some(30));
+        assert_eq!(stale.keys().collect::<Vec<_>>(), [&Stale::Untouched(30)]);
+        let mut paths = stale[&Stale::Untouched(30)].clone();
+        paths.sort();
+        let debug = dir.path().join("debug");
+        assert_eq!(
+            paths,
+            [
+                debug.join(".fingerprint/old-0123456789abcdef"),
+                debug.join("build/old-0123456789abcdef"),
+                debug.join("deps/libold-0123456789abcdef.rlib"),
+            ]
+        );
+    }
+}
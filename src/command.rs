@@ -1,5 +1,12 @@
+use std::path::PathBuf;
+
 use clap::Parser;
+
+use crate::cargo::Clean;
 pub mod clear;
+pub mod history;
+pub mod quarantine;
+pub mod scan;
 
 #[derive(Parser)]
 #[clap(
@@ -9,24 +16,58 @@ pub mod clear;
     about = "a clear dev junk file."
 )]
 pub struct Cli {
+    /// extra scan rules file, merged over ~/.config/rmdev/rules.toml
+    #[clap(long, global = true)]
+    pub rules: Option<PathBuf>,
+
+    /// sizes in powers of 1000 (kB, MB, GB) instead of 1024 (KiB, MiB, GiB)
+    #[clap(long, global = true, conflicts_with = "bytes")]
+    pub si: bool,
+
+    /// sizes as plain byte counts, eg: for scripts
+    #[clap(long, global = true)]
+    pub bytes: bool,
+
     #[clap(subcommand)]
     pub commands: Commands,
 }
 
+/// `--cargo` and `--sweep-days`, taken by the commands that scan.
+#[derive(clap::Args, Debug)]
+pub struct CargoArgs {
+    /// part of a Cargo target dir to remove: all, incremental, debug, doc,
+    /// except-release, sweep (stale toolchains) or a target triple
+    #[clap(long, value_name = "PART", default_value = "all")]
+    pub cargo: Clean,
+
+    /// with `--cargo sweep`, also remove units not rebuilt in this many days
+    #[clap(long, value_name = "DAYS")]
+    pub sweep_days: Option<u64>,
+}
+
+impl CargoArgs {
+    /// The part to remove, `--sweep-days` folded into a sweep.
+    pub fn clean(&self) -> Result<Clean, Box<dyn std::error::Error>> {
+        match (&self.cargo, self.sweep_days) {
+            (Clean::Sweep(_), days) => Ok(Clean::Sweep(days)),
+            (_, Some(_)) => Err("--sweep-days only applies to --cargo sweep".into()),
+            (clean, None) => Ok(clean.clone()),
+        }
+    }
+}
+
 #[derive(Parser)]
 pub enum Commands {
     /// clear junk file
     Clear(clear::Clear),
-    // /// start a tui.
-    // UI(RunUI),
+    /// report junk file sizes without removing anything
+    Scan(scan::Scan),
+    /// restore the last quarantined clear
+    Undo(quarantine::Undo),
+    /// list or restore quarantined clears
+    Restore(quarantine::Restore),
+    /// delete quarantined clears for good
+    Purge(quarantine::Purge),
+    /// list past removals and the space they reclaimed
+    History(history::History),
 }
-
-// #[derive(clap::Parser, Debug)]
-// pub struct RunUI {
-//     /// eg: qxg
-//     pub target: String,
-
-//     /// regex filter oplog
-//     #[clap(short, long)]
-//     pub filter: Option<String>,
-// }
@@ -1,14 +1,28 @@
 use std::{
     collections::HashSet,
-    fs, io,
+    fs,
+    io::{self, IsTerminal},
     path::{Path, PathBuf},
     process::exit,
-    sync::{Arc, Mutex, RwLock},
+    sync::{mpsc, Arc, Mutex, RwLock},
     thread::{self, spawn},
 };
 
+use serde::Deserialize;
+
 use crate::{
-    scan_category::{self, ScanCate},
+    cachedir,
+    cargo::{self, Clean},
+    command::CargoArgs,
+    config,
+    engine::{self, Progress},
+    journal::{ConfirmMode, Journal},
+    output::{Format, Output, Record, RecordKind},
+    quarantine,
+    scan_category::{self, Layout, ScanCate},
+    signal::{ClearStatus, RowState, ScanStatus, CLEAR_DONE, CLEAR_RUNNING},
+    size::{format_rate, format_size},
+    trash,
     ui::{self, UI},
 };
 
@@ -21,60 +35,322 @@ pub struct Clear {
     #[clap(short, long)]
     pub force: bool,
 
-    /// ci env
+    /// ci env, never prompts so it needs `--force` or `--dry-run`
     #[clap(short, long)]
     pub ci: bool,
+
+    /// only print what would be removed
+    #[clap(long)]
+    pub dry_run: bool,
+
+    /// what to do with removed directories, default from `mode` in config.toml
+    #[clap(long, value_enum)]
+    pub mode: Option<RemoveMode>,
+
+    /// shorthand for `--mode trash`
+    #[clap(long, conflicts_with_all = ["mode", "quarantine"])]
+    pub trash: bool,
+
+    /// shorthand for `--mode quarantine`
+    #[clap(long, conflicts_with = "mode")]
+    pub quarantine: bool,
+
+    /// output format of scanned rows and removal results
+    #[clap(long, value_enum, default_value_t = Format::Table)]
+    pub format: Format,
+
+    /// directories removed in parallel
+    #[clap(short, long, default_value_t = default_jobs())]
+    pub jobs: usize,
+
+    #[clap(flatten)]
+    pub cargo: CargoArgs,
+}
+
+fn default_jobs() -> usize {
+    thread::available_parallelism().map_or(4, |n| n.get())
 }
 
 impl Clear {
     pub async fn run(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if self.ci && !self.force && !self.dry_run {
+            return Err(
+                "--ci never asks for confirmation, pass --force to remove or --dry-run to preview"
+                    .into(),
+            );
+        }
+
+        let clean = self.cargo.clean()?;
         let rows = Arc::new(Mutex::new(Vec::new()));
 
         if self.ci {
-            scan_target(self.target.clone().into(), rows.clone()).await?;
-            let removed_count = clear_target(rows.clone(), self.force)?;
-            println!("[RM] Clear {removed_count} project cache.");
+            let output = Arc::new(Output::new(self.format));
+            scan_target(
+                self.target.clone().into(),
+                rows.clone(),
+                Some(output.clone()),
+                Arc::new(ScanStatus::new()),
+                clean.clone(),
+            )
+            .await?;
+            let rows = rows.lock().unwrap();
+            self.clear(&rows.iter().collect::<Vec<_>>(), &clean, &output, None)?;
+            output.finish()?;
         } else {
-            // println!("Scan rows: {:?}", rows);
+            // the TUI owns the terminal, results are printed once it is closed
+            let output = Output::deferred(self.format);
+            let selected = Arc::new(Mutex::new(HashSet::new()));
+            let status = Arc::new(ScanStatus::new());
+            let clear_status = Arc::new(ClearStatus::default());
+            let (request, requested) = mpsc::channel();
             let th = spawn({
                 let rows = rows.clone();
+                let selected = selected.clone();
+                let status = status.clone();
+                let clear_status = clear_status.clone();
+                let (force, dry_run, clean) = (self.force, self.dry_run, clean.clone());
                 move || {
-                    let code = ui::boot(UI { rows }).unwrap();
-                    if code != 0 {
+                    let code = ui::boot(UI {
+                        rows,
+                        selected,
+                        status,
+                        clear: clear_status.clone(),
+                        request,
+                        force,
+                        dry_run,
+                        clean,
+                    })
+                    .unwrap();
+                    // nothing to report if the user left before clearing
+                    if code != 0 && !clear_status.is_done() {
                         exit(0);
                     }
-                    return code;
+                    code
                 }
             });
 
-            scan_target(self.target.clone().into(), rows.clone()).await?;
-
-            let code = th.join().unwrap();
+            scan_target(
+                self.target.clone().into(),
+                rows.clone(),
+                None,
+                status,
+                clean.clone(),
+            )
+            .await?;
 
-            if code == 0 {
-                let removed_count = clear_target(rows.clone(), self.force)?;
-                println!("[RM] Clear {removed_count} project cache.");
+            // wait for the confirmation in the TUI, the sender is dropped if it quits instead
+            let mut result = Ok(());
+            if let Ok(confirmed) = requested.recv() {
+                // copied out so the TUI can keep locking them to draw
+                let selected = selected.lock().unwrap().clone();
+                let (rows, skipped): (Vec<_>, Vec<_>) = rows
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .filter(|row| selected.contains(&row.key()))
+                    .cloned()
+                    .partition(|row| confirmed.contains(&row.key()));
+                let skipped = skipped.iter().collect::<Vec<_>>();
+                for record in plan_target(&skipped, &clean, RecordKind::Skipped) {
+                    output.emit(record);
+                }
+                clear_status.state.set(CLEAR_RUNNING);
+                result = self.clear(
+                    &rows.iter().collect::<Vec<_>>(),
+                    &clean,
+                    &output,
+                    Some(clear_status.clone()),
+                );
+                // shown by the TUI, returned once the user closed it
+                if let Err(err) = &result {
+                    clear_status.fail(err);
+                }
+                clear_status.state.set(CLEAR_DONE);
             }
+
+            th.join().unwrap();
+            output.finish()?;
+            result?;
         }
 
         Ok(())
     }
+
+    fn clear(
+        &self,
+        rows: &[&ScanRow],
+        clean: &Clean,
+        output: &Output,
+        status: Option<Arc<ClearStatus>>,
+    ) -> io::Result<()> {
+        if self.dry_run {
+            if let Some(status) = &status {
+                for row in rows {
+                    status.set_row(row.key(), RowState::Planned(row.size));
+                }
+            }
+            let plan = plan_target(rows, clean, RecordKind::Plan);
+            let (count, size) = (plan.len(), plan.iter().map(|r| r.size).sum::<u64>());
+            plan.into_iter().for_each(|record| output.emit(record));
+            output.note(format!(
+                "[PLAN] Would remove {count} directories, {} in total.",
+                format_size(size)
+            ));
+        } else {
+            // only on a real clear, a preview or a quit TUI leaves old runs undoable
+            let journal = Journal::open(self.confirm_mode());
+            if let Err(err) = quarantine::purge_expired(config::config().quarantine_days, &journal)
+            {
+                let note = format!("[RM] Purge expired quarantine Error: {err}");
+                match status {
+                    // stderr would draw over the TUI
+                    Some(_) => output.note(note),
+                    None => eprintln!("{note}"),
+                }
+            }
+            let remover = match self.remove_mode() {
+                RemoveMode::Delete => Remover::Delete,
+                RemoveMode::Trash => Remover::Trash,
+                RemoveMode::Quarantine => {
+                    Remover::Quarantine(quarantine::Run::begin(Path::new(&self.target))?)
+                }
+            };
+            let ctx = ClearContext {
+                jobs: self.jobs,
+                clean,
+                remover,
+                output,
+                journal,
+                progress: status
+                    .as_ref()
+                    .map_or_else(Arc::default, |status| status.progress.clone()),
+                status,
+            };
+            let removed_count = clear_target(rows, &ctx)?;
+            output.note(format!("[RM] Clear {removed_count} project cache."));
+            if let Remover::Quarantine(run) = ctx.remover {
+                if let Some(id) = run.finish()? {
+                    output.note(format!(
+                        "[RM] Quarantined as run {id}, `rmdev undo` to restore it."
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn confirm_mode(&self) -> ConfirmMode {
+        if self.ci {
+            ConfirmMode::Ci
+        } else if self.force {
+            ConfirmMode::Force
+        } else {
+            ConfirmMode::Confirm
+        }
+    }
+
+    fn remove_mode(&self) -> RemoveMode {
+        if self.trash {
+            RemoveMode::Trash
+        } else if self.quarantine {
+            RemoveMode::Quarantine
+        } else {
+            self.mode.unwrap_or(config::config().mode)
+        }
+    }
+}
+
+/// What happens to an artifact directory once it is confirmed for removal.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RemoveMode {
+    /// delete permanently
+    #[default]
+    Delete,
+    /// move to the system trash
+    Trash,
+    /// move aside until purged, see `rmdev undo`
+    Quarantine,
+}
+
+enum Remover {
+    Delete,
+    Trash,
+    Quarantine(quarantine::Run),
+}
+
+impl Remover {
+    /// Returns what happened and how many bytes left the project.
+    fn remove(
+        &self,
+        path: &Path,
+        row: &ScanRow,
+        progress: &Progress,
+    ) -> io::Result<(RecordKind, u64)> {
+        match self {
+            Self::Delete => {
+                let size = engine::remove_dir_all(path, progress)?;
+                Ok((RecordKind::Removed, size))
+            }
+            // moves are a single rename, so the size is measured up front
+            Self::Trash => {
+                let size = get_directory_size(path, Visited::default()).unwrap_or(0);
+                trash::move_to_trash(path)?;
+                progress.add(0, size);
+                Ok((RecordKind::Trashed, size))
+            }
+            Self::Quarantine(run) => {
+                let size = get_directory_size(path, Visited::default()).unwrap_or(0);
+                run.stage(path, &row.project, &row.cate.ident(), size)?;
+                progress.add(0, size);
+                Ok((RecordKind::Quarantined, size))
+            }
+        }
+    }
+}
+
+/// Everything `clear_target` needs besides the rows.
+struct ClearContext<'a> {
+    /// worker threads removing directories
+    jobs: usize,
+    clean: &'a Clean,
+    remover: Remover,
+    output: &'a Output,
+    journal: Journal,
+    progress: Arc<Progress>,
+    /// set when the TUI shows the clear
+    status: Option<Arc<ClearStatus>>,
 }
 
-#[derive(Debug)]
+impl ClearContext<'_> {
+    fn set_row(&self, row: &ScanRow, state: RowState) {
+        if let Some(status) = &self.status {
+            status.set_row(row.key(), state);
+        }
+    }
+}
+
+/// Identifies a row, a project matching several categories has a row for each.
+pub type RowKey = (PathBuf, String);
+
+#[derive(Debug, Clone)]
 pub struct ScanRow {
     pub path: PathBuf,
     pub project: String,
     pub cate: ScanCate,
-    pub size: u64, // Bytes
+    pub size: u64, // Bytes of the artifacts, not the whole project
 }
 
 impl ScanRow {
+    pub fn key(&self) -> RowKey {
+        (self.path.clone(), self.cate.ident())
+    }
+
     pub fn ref_data(&self) -> [String; 4] {
         [
             self.project.clone(),
             self.cate.ident(),
-            format!("{:.2}GB", (self.size as f64) / 1024.0 / 1024.0 / 1024.0),
+            format_size(self.size),
             format!("{:?}", self.path.to_str().unwrap()),
         ]
     }
@@ -84,57 +360,111 @@ impl ScanRow {
     }
 }
 
-fn clear_target(rows: Arc<Mutex<Vec<ScanRow>>>, force: bool) -> io::Result<usize> {
-    let mut removed_count: usize = 0;
-    let rows = rows.lock().unwrap();
-    for row in rows.iter() {
-        match traverse_rm(row.path.clone(), row.cate.clone(), force) {
-            Ok(count) => {
-                if count > 0 {
-                    println!("[RM] {:?} success remove {}.", row.path, count);
-                    removed_count += 1;
+fn clear_target(rows: &[&ScanRow], ctx: &ClearContext) -> io::Result<usize> {
+    let mut jobs = Vec::new();
+    let mut remaining = vec![0usize; rows.len()];
+    for (i, row) in rows.iter().enumerate() {
+        ctx.set_row(row, RowState::Pending);
+        for path in find_artifacts(&row.path, &row.cate, ctx.clean) {
+            jobs.push((i, path));
+            remaining[i] += 1;
+        }
+        if remaining[i] == 0 {
+            ctx.set_row(row, RowState::Removed(0));
+        }
+    }
+
+    let draw = ctx.status.is_none() && io::stderr().is_terminal();
+    let mut removed = vec![0usize; rows.len()];
+    let mut freed = vec![0u64; rows.len()];
+    let mut errors = vec![Vec::new(); rows.len()];
+    engine::run(
+        jobs,
+        ctx.jobs,
+        &ctx.progress,
+        |(i, path)| {
+            ctx.set_row(rows[*i], RowState::Deleting);
+            ctx.remover.remove(path, rows[*i], &ctx.progress)
+        },
+        |(i, path), result| {
+            let record = match result {
+                Ok((kind, size)) => {
+                    removed[i] += 1;
+                    freed[i] += size;
+                    Record::new(kind, path, rows[i], size)
                 }
-                // else {
-                //     println!("[RM] {:?} no need to remove.", row.path);
-                // }
+                Err(err) => {
+                    errors[i].push(err.to_string());
+                    Record::new(RecordKind::Failed, path, rows[i], 0).with_error(err)
+                }
+            };
+            remaining[i] -= 1;
+            if remaining[i] == 0 {
+                let state = if errors[i].is_empty() {
+                    RowState::Removed(freed[i])
+                } else {
+                    RowState::Failed(freed[i], errors[i].join("; "))
+                };
+                ctx.set_row(rows[i], state);
             }
-            Err(err) => {
-                eprintln!("[RM] {:?} Error: {}", row.path, err);
+            if draw {
+                eprint!("\r\x1b[K");
             }
-        }
+            ctx.journal.append(&record);
+            ctx.output.emit(record);
+        },
+        || {
+            if draw {
+                draw_progress(&ctx.progress);
+            }
+        },
+    )?;
+    if draw {
+        eprintln!();
     }
-    Ok(removed_count)
+
+    Ok(removed.into_iter().filter(|count| *count > 0).count())
 }
 
-fn traverse_rm(path: PathBuf, cate: ScanCate, force: bool) -> io::Result<usize> {
-    let mut stack = vec![path];
-    let mut removed_count: usize = 0;
+fn draw_progress(progress: &Progress) {
+    const WIDTH: usize = 20;
+    let (done, total) = progress.jobs();
+    let filled = (progress.ratio() * WIDTH as f64) as usize;
+    eprint!(
+        "\r\x1b[K[RM] [{}{}] {done}/{total} dirs  {} files  {}  {}  {:.0} files/s",
+        "#".repeat(filled),
+        "-".repeat(WIDTH - filled),
+        progress.files(),
+        format_size(progress.bytes()),
+        format_rate(progress.bytes_per_sec()),
+        progress.files_per_sec()
+    );
+}
 
-    while let Some(path) = stack.pop() {
-        if cate.rm_keyfile(&path) {
-            let mut remove_yes = false;
-            if force {
-                remove_yes = true;
-            } else {
-                let confirmation = dialoguer::Confirm::new()
-                    .with_prompt(format!(
-                        "[RM] The {path:?} directory is about to be remove, Do you want to continue?"
-                    ))
-                    .interact()
-                    .unwrap();
+/// Walk the project `root` and collect the directories `cate` would remove,
+/// of a Cargo target dir the part `clean` selects.
+pub(crate) fn find_artifacts(root: &Path, cate: &ScanCate, clean: &Clean) -> Vec<PathBuf> {
+    match cate.layout() {
+        Layout::Cargo => return cargo::artifacts(root, cate, clean),
+        Layout::Cachedir if cate.is_cache_dir(root) => return vec![root.to_path_buf()],
+        Layout::Cachedir => return Vec::new(),
+        Layout::Plain => {}
+    }
+    let mut stack = vec![root.to_path_buf()];
+    let mut artifacts = Vec::new();
 
-                if confirmation {
-                    remove_yes = true;
-                }
-            }
-            if remove_yes {
-                if let Ok(_) = fs::remove_dir_all(path) {
-                    removed_count += 1;
-                }
+    while let Some(path) = stack.pop() {
+        if cate.rm_keyfile(root, &path) {
+            if cate.owns(&path) {
+                artifacts.push(path);
             }
             continue;
         }
-        if path.is_dir() {
+        // no need to look deeper than the artifacts of the category reach
+        let depth = path
+            .strip_prefix(root)
+            .map_or(0, |rel| rel.components().count());
+        if depth < cate.artifact_depth() && path.is_dir() {
             let dir = fs::read_dir(path);
             if let Ok(dir) = dir {
                 for entry in dir {
@@ -148,53 +478,116 @@ fn traverse_rm(path: PathBuf, cate: ScanCate, force: bool) -> io::Result<usize>
         }
     }
 
-    Ok(removed_count)
+    artifacts
 }
 
-async fn scan_target(path: PathBuf, rows: Arc<Mutex<Vec<ScanRow>>>) -> io::Result<()> {
+/// What `clear_target` would remove as `kind` records, without touching the filesystem.
+fn plan_target(rows: &[&ScanRow], clean: &Clean, kind: RecordKind) -> Vec<Record> {
+    rows.iter()
+        .flat_map(|row| {
+            find_artifacts(&row.path, &row.cate, clean)
+                .into_iter()
+                .map(|path| {
+                    let size = get_directory_size(&path, Visited::default()).unwrap_or(0);
+                    Record::new(kind, path, row, size)
+                })
+        })
+        .collect()
+}
+
+/// Fill `rows` with the projects under `path`, streaming each one to `output` if given
+/// and reporting how far it got through `status`.
+pub(crate) async fn scan_target(
+    path: PathBuf,
+    rows: Arc<Mutex<Vec<ScanRow>>>,
+    output: Option<Arc<Output>>,
+    status: Arc<ScanStatus>,
+    clean: Clean,
+) -> io::Result<()> {
+    // artifacts outside of it, eg: a `CARGO_TARGET_DIR` for every project, are not ours to list
+    let scan_root = Arc::new(path.canonicalize()?);
     let mut stack = vec![path];
     let visited = Arc::new(RwLock::new(HashSet::new()));
 
     while let Some(path) = stack.pop() {
-        // sleep(std::time::Duration::from_nanos(1)).await;
         if path.is_dir() {
-            // println!("Directory: {:?}", path);
-            let _ = tokio::spawn({
+            status.visit(&path);
+            tokio::spawn({
                 let path = path.clone();
                 let scan_rows = rows.clone();
                 let visited = visited.clone();
+                let output = output.clone();
+                let scan_root = scan_root.clone();
+                let clean = clean.clone();
                 async move {
                     let is_skip = path.join("rmdev.skip").exists();
                     if is_skip {
                         return;
                     }
 
-                    for cate in scan_category::STRATEGY.iter() {
-                        if cate.access_keyfile(&path) {
-                            let mut scan_rows = scan_rows.lock().unwrap();
-                            let path = path.canonicalize().unwrap();
-                            let project = path.file_name().unwrap().to_str().unwrap().to_string();
-                            scan_rows.push(ScanRow {
-                                path: path.clone(),
-                                cate: cate.clone(),
-                                size: get_directory_size(&path, visited).unwrap(),
-                                // size: scan_size(path, &mut HashSet::new()).unwrap(),
-                                // size: 0,
-                                project,
-                            });
-                            return;
+                    // a polyglot project gets a row for every category it matches
+                    for cate in scan_category::strategy().iter() {
+                        if !cate.access_keyfile(&path) {
+                            continue;
+                        }
+                        let path = path.canonicalize().unwrap();
+                        // a target dir shared by several workspaces belongs to the first one
+                        let artifacts = find_artifacts(&path, cate, &clean)
+                            .into_iter()
+                            .filter(|artifact| {
+                                artifact.starts_with(scan_root.as_path())
+                                    && !visited.read().unwrap().contains(artifact)
+                            })
+                            .collect::<Vec<_>>();
+                        if artifacts.is_empty() {
+                            continue;
+                        }
+                        let mut project = path.file_name().unwrap().to_str().unwrap().to_string();
+                        match cate.layout() {
+                            Layout::Cachedir => {
+                                if let Some(Some(tool)) = cachedir::tag(&path) {
+                                    project = format!("{project} ({tool})");
+                                }
+                            }
+                            // clearing it clears every project building into it
+                            Layout::Cargo
+                                if artifacts
+                                    .iter()
+                                    .any(|artifact| !artifact.starts_with(&path)) =>
+                            {
+                                project = format!("{project} (shared target)");
+                            }
+                            _ => {}
                         }
+                        let row = ScanRow {
+                            size: artifacts
+                                .iter()
+                                .map(|artifact| {
+                                    get_directory_size(artifact, visited.clone()).unwrap_or(0)
+                                })
+                                .sum(),
+                            path,
+                            cate: cate.clone(),
+                            project,
+                        };
+                        if let Some(output) = &output {
+                            output.emit(Record::from(&row));
+                        }
+                        // sized before locking so the TUI keeps drawing meanwhile
+                        scan_rows.lock().unwrap().push(row);
                     }
                 }
             })
             .await?;
-            if path.ends_with(".git") {
+            if path.ends_with(".git") || path.ends_with(quarantine::STAGING_DIR) {
                 continue;
             }
-            if scan_category::STRATEGY
+            if scan_category::strategy()
                 .iter()
-                .find(|cate| cate.rm_keyfile(&path))
-                .is_some()
+                .any(|cate| cate.is_artifact(&path))
+                || path
+                    .canonicalize()
+                    .is_ok_and(|path| visited.read().unwrap().contains(&path))
             {
                 continue;
             }
@@ -211,39 +604,14 @@ async fn scan_target(path: PathBuf, rows: Arc<Mutex<Vec<ScanRow>>>) -> io::Resul
         }
     }
 
+    status.finish();
     Ok(())
 }
 
-fn scan_size(path: PathBuf, visited: &mut HashSet<PathBuf>) -> io::Result<u64> {
-    let mut stack: Vec<PathBuf> = vec![path];
-    let mut size = 0;
-
-    while let Some(path) = stack.pop() {
-        thread::sleep(std::time::Duration::from_nanos(1));
-        if visited.contains(&path) {
-            continue;
-        }
-        visited.insert(path.clone());
-        if path.is_dir() {
-            for entry in fs::read_dir(path)? {
-                let entry = entry?;
-                let entry_path = entry.path();
-                stack.push(entry_path);
-            }
-        } else {
-            if let Ok(path) = path.metadata() {
-                size += path.len();
-            }
-        }
-    }
-
-    Ok(size)
-}
-
-type Visited = Arc<RwLock<HashSet<PathBuf>>>;
+pub(crate) type Visited = Arc<RwLock<HashSet<PathBuf>>>;
 
 /// 递归计算目录的总大小（以字节为单位），并避免死循环。
-fn get_directory_size(path: &Path, visited: Visited) -> io::Result<u64> {
+pub(crate) fn get_directory_size(path: &Path, visited: Visited) -> io::Result<u64> {
     use rayon::prelude::*;
 
     let mut total_size = 0;
@@ -256,6 +624,11 @@ fn get_directory_size(path: &Path, visited: Visited) -> io::Result<u64> {
         }
     }
 
+    let metadata = fs::metadata(path)?;
+    if !metadata.is_dir() {
+        return Ok(metadata.len());
+    }
+
     let entries: Vec<_> = fs::read_dir(path)?.collect::<Result<Vec<_>, io::Error>>()?;
 
     // 使用 Rayon 并行处理目录和文件
@@ -279,3 +652,63 @@ fn get_directory_size(path: &Path, visited: Visited) -> io::Result<u64> {
 
     Ok(total_size)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cargo::BuildEnv, testing::TempDir};
+
+    #[test]
+    fn cachedir_leaves_a_partly_cleaned_cargo_target_to_cargo() {
+        // neither the user's rules nor a `CARGO_TARGET_DIR` may move the target
+        scan_category::load_files([]).unwrap();
+        cargo::init_env(BuildEnv::default()).unwrap();
+        let clean = Clean::Incremental;
+
+        let dir = TempDir::new("cachedir");
+        dir.dirs(&["app/target/debug/incremental", "app/target/release"])
+            .file("app/Cargo.toml", "[package]\nname = \"app\"\n")
+            .tag("app/target", "cargo")
+            .tag("ccache", "ccache");
+        let root = dir.path();
+        let (project, target) = (root.join("app"), root.join("app/target"));
+
+        let cate = |ident: &str| {
+            scan_category::strategy()
+                .iter()
+                .find(|cate| cate.ident() == ident)
+                .unwrap()
+        };
+        assert_eq!(
+            find_artifacts(&project, cate("Cargo"), &clean),
+            vec![target.join("debug/incremental")]
+        );
+        assert!(find_artifacts(&target, cate("CacheDir"), &clean).is_empty());
+
+        let rows = Arc::new(Mutex::new(Vec::new()));
+        tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(scan_target(
+                root.to_path_buf(),
+                rows.clone(),
+                None,
+                Arc::new(ScanStatus::new()),
+                clean.clone(),
+            ))
+            .unwrap();
+        let mut keys = rows
+            .lock()
+            .unwrap()
+            .iter()
+            .map(ScanRow::key)
+            .collect::<Vec<_>>();
+        keys.sort();
+        assert_eq!(
+            keys,
+            vec![
+                (project, "Cargo".to_string()),
+                (root.join("ccache"), "CacheDir".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use chrono::{Local, NaiveDate, TimeZone};
+
+use crate::{
+    journal::{self, JournalEntry},
+    output::{self, Format},
+    size::format_size,
+};
+
+#[derive(clap::Parser, Debug)]
+pub struct History {
+    /// only entries on or after this day, eg: 2024-07-01
+    #[clap(long)]
+    pub since: Option<NaiveDate>,
+
+    /// only entries on or before this day
+    #[clap(long)]
+    pub until: Option<NaiveDate>,
+
+    /// only entries whose project name or path contains this
+    #[clap(short, long)]
+    pub project: Option<String>,
+
+    /// output format of the entries
+    #[clap(long, value_enum, default_value_t = Format::Table)]
+    pub format: Format,
+}
+
+impl History {
+    pub async fn run(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let entries = journal::read()?
+            .into_iter()
+            .filter(|entry| self.matches(entry))
+            .collect::<Vec<_>>();
+
+        if self.format != Format::Table {
+            output::print_all(self.format, &entries)?;
+            return Ok(());
+        }
+
+        for entry in &entries {
+            println!(
+                "{}  {:<11}  {:>10}  {:<8}  {:<8}  {:?}{}",
+                entry.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                format!("{:?}", entry.result).to_lowercase(),
+                format_size(entry.size),
+                entry.cate,
+                entry.user,
+                entry.path,
+                entry
+                    .error
+                    .as_ref()
+                    .map(|err| format!("  Error: {err}"))
+                    .unwrap_or_default()
+            );
+        }
+        let sum = |keep: fn(&JournalEntry) -> bool| {
+            entries
+                .iter()
+                .filter(|entry| keep(entry))
+                .map(|entry| entry.size)
+                .sum::<u64>()
+        };
+        // a range can hold the restore of a quarantine from before it
+        let freed = sum(JournalEntry::is_freed).saturating_sub(sum(JournalEntry::is_restored));
+        println!(
+            "[HISTORY] {} entries, {} reclaimed.",
+            entries.len(),
+            format_size(freed)
+        );
+        Ok(())
+    }
+
+    fn matches(&self, entry: &JournalEntry) -> bool {
+        let day = |date: NaiveDate| {
+            Local
+                .from_local_datetime(&date.and_hms_opt(0, 0, 0).unwrap())
+                .earliest()
+        };
+        if let Some(since) = self.since.and_then(day) {
+            if entry.timestamp < since {
+                return false;
+            }
+        }
+        if let Some(until) = self.until.and_then(|until| until.succ_opt()).and_then(day) {
+            if entry.timestamp >= until {
+                return false;
+            }
+        }
+        if let Some(project) = &self.project {
+            return entry.project.contains(project.as_str())
+                || entry.path.to_string_lossy().contains(project.as_str());
+        }
+        true
+    }
+}
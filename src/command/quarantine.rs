@@ -0,0 +1,109 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    journal::{ConfirmMode, Journal},
+    quarantine::{self, Manifest},
+    size::format_size,
+};
+
+#[derive(clap::Parser, Debug)]
+pub struct Undo {}
+
+#[derive(clap::Parser, Debug)]
+pub struct Restore {
+    /// run id shown by `rmdev restore`, lists the runs when omitted
+    pub run_id: Option<String>,
+
+    /// only restore entries at or under this original path
+    pub path: Option<PathBuf>,
+}
+
+#[derive(clap::Parser, Debug)]
+pub struct Purge {
+    /// only purge runs older than `quarantine_days` in config.toml
+    #[clap(long)]
+    pub expired: bool,
+}
+
+impl Undo {
+    pub async fn run(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(mut manifest) = quarantine::runs()?.pop() else {
+            println!("[RM] Nothing to undo.");
+            return Ok(());
+        };
+        restore(&mut manifest, None);
+        Ok(())
+    }
+}
+
+impl Restore {
+    pub async fn run(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let runs = quarantine::runs()?;
+        let Some(run_id) = &self.run_id else {
+            for run in runs.iter().rev() {
+                println!(
+                    "{}  {}  {:>3} entries  {}",
+                    run.id,
+                    run.created_at.format("%Y-%m-%d %H:%M:%S"),
+                    run.entries.len(),
+                    format_size(run.size())
+                );
+            }
+            return Ok(());
+        };
+
+        let mut manifest = runs
+            .into_iter()
+            .find(|run| &run.id == run_id)
+            .ok_or_else(|| format!("no quarantined run {run_id:?}"))?;
+        let filter = self.path.as_deref().map(resolve).transpose()?;
+        restore(&mut manifest, filter.as_deref());
+        Ok(())
+    }
+}
+
+impl Purge {
+    pub async fn run(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let journal = Journal::open(ConfirmMode::Confirm);
+        let freed = if self.expired {
+            quarantine::purge_expired(crate::config::config().quarantine_days, &journal)?
+        } else {
+            let mut freed = 0;
+            for run in quarantine::runs()? {
+                freed += run.purge(&journal)?;
+            }
+            freed
+        };
+        println!("[RM] Purged {} of quarantine.", format_size(freed));
+        Ok(())
+    }
+}
+
+/// `path` as the absolute path it was quarantined from. The artifact itself is gone,
+/// so only its parent is canonicalized, eg: `p/node_modules` to `/home/me/p/node_modules`.
+fn resolve(path: &Path) -> std::io::Result<PathBuf> {
+    let path = env::current_dir()?.join(path);
+    if let Ok(path) = path.canonicalize() {
+        return Ok(path);
+    }
+    Ok(match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => parent.canonicalize().map_or(path.clone(), |p| p.join(name)),
+        _ => path,
+    })
+}
+
+fn restore(manifest: &mut Manifest, filter: Option<&Path>) {
+    let results = manifest.restore(filter, &Journal::open(ConfirmMode::Confirm));
+    if results.is_empty() {
+        println!("[RM] Nothing to restore in run {}.", manifest.id);
+    }
+    for (path, result) in results {
+        match result {
+            Ok(()) => println!("[RM] {path:?} restored."),
+            Err(err) => eprintln!("[RM] {path:?} Error: {err}"),
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use std::{
+    cmp::Reverse,
+    collections::BTreeMap,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    output::{Format, Output, Record},
+    signal::ScanStatus,
+    size::format_size,
+};
+
+use super::{
+    clear::{scan_target, ScanRow},
+    CargoArgs,
+};
+
+/// Only reports, nothing in here is able to remove a file.
+#[derive(clap::Parser, Debug)]
+pub struct Scan {
+    /// scan target dir
+    pub target: String,
+
+    /// output format of scanned rows
+    #[clap(long, value_enum, default_value_t = Format::Table)]
+    pub format: Format,
+
+    #[clap(flatten)]
+    pub cargo: CargoArgs,
+}
+
+impl Scan {
+    pub async fn run(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let rows = Arc::new(Mutex::new(Vec::new()));
+        let output = Arc::new(Output::new(self.format));
+
+        // streaming formats report rows as they are found, the others once sorted
+        let streaming = matches!(self.format, Format::Ndjson | Format::Csv);
+        scan_target(
+            self.target.clone().into(),
+            rows.clone(),
+            streaming.then(|| output.clone()),
+            Arc::new(ScanStatus::new()),
+            self.cargo.clean()?,
+        )
+        .await?;
+
+        let mut rows = rows.lock().unwrap();
+        rows.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+
+        if self.format == Format::Table {
+            print_summary(&rows);
+        } else if !streaming {
+            rows.iter().for_each(|row| output.emit(Record::from(row)));
+        }
+        output.finish()?;
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct Group {
+    count: usize,
+    size: u64,
+}
+
+fn print_summary(rows: &[ScanRow]) {
+    let mut by_cate: BTreeMap<String, Group> = BTreeMap::new();
+    let mut by_project: BTreeMap<PathBuf, (String, Group)> = BTreeMap::new();
+    for row in rows {
+        let group = by_cate.entry(row.cate.ident()).or_default();
+        group.count += 1;
+        group.size += row.size;
+
+        let (_, group) = by_project
+            .entry(row.path.clone())
+            .or_insert_with(|| (row.project.clone(), Group::default()));
+        group.count += 1;
+        group.size += row.size;
+    }
+
+    let mut by_cate = by_cate.into_iter().collect::<Vec<_>>();
+    by_cate.sort_by_key(|(_, group)| Reverse(group.size));
+    let mut by_project = by_project.into_iter().collect::<Vec<_>>();
+    let projects = by_project.len();
+    by_project.sort_by_key(|(_, (_, group))| Reverse(group.size));
+
+    println!("[SCAN] By category:");
+    for (ident, group) in by_cate {
+        println!(
+            "  {:<10} {:>5} projects  {:>10}",
+            ident,
+            group.count,
+            format_size(group.size)
+        );
+    }
+    println!("[SCAN] By project:");
+    for (path, (project, group)) in by_project {
+        println!(
+            "  {:>10}  {:<20}  {:?}",
+            format_size(group.size),
+            project,
+            path
+        );
+    }
+    println!(
+        "[SCAN] {} projects, {} in total.",
+        projects,
+        format_size(rows.iter().map(|r| r.size).sum())
+    );
+}
@@ -0,0 +1,40 @@
+use std::fs;
+
+use educe::Educe;
+use once_cell::sync::OnceCell;
+use serde::Deserialize;
+
+use crate::{command::clear::RemoveMode, paths};
+
+static CONFIG: OnceCell<Config> = OnceCell::new();
+
+/// Defaults from `~/.config/rmdev/config.toml`, command line flags win over them.
+#[derive(Debug, Educe, Deserialize)]
+#[educe(Default)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct Config {
+    /// what `clear` does with artifacts: delete, trash or quarantine
+    pub(crate) mode: RemoveMode,
+    /// days a quarantined run is kept before `clear` purges it
+    #[educe(Default = 7)]
+    pub(crate) quarantine_days: u64,
+}
+
+pub(crate) fn config() -> &'static Config {
+    CONFIG.get().expect("config is not loaded")
+}
+
+pub(crate) fn load() -> anyhow::Result<()> {
+    let mut config = Config::default();
+    if let Some(path) = paths::config_dir().map(|dir| dir.join("config.toml")) {
+        if path.exists() {
+            let content = fs::read_to_string(&path)
+                .map_err(|err| anyhow::anyhow!("read config file {path:?}: {err}"))?;
+            config = toml::from_str(&content)
+                .map_err(|err| anyhow::anyhow!("parse config file {path:?}: {err}"))?;
+        }
+    }
+    CONFIG
+        .set(config)
+        .map_err(|_| anyhow::anyhow!("config is already loaded"))
+}
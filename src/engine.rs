@@ -0,0 +1,159 @@
+use std::{
+    fs, io,
+    path::Path,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        mpsc::{self, RecvTimeoutError},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use rayon::prelude::*;
+
+const TICK: Duration = Duration::from_millis(100);
+
+/// Counters shared by the workers, readable from any thread while they run.
+#[derive(Debug)]
+pub(crate) struct Progress {
+    bytes: AtomicU64,
+    files: AtomicU64,
+    jobs_done: AtomicUsize,
+    jobs_total: AtomicUsize,
+    started: Instant,
+}
+
+impl Default for Progress {
+    fn default() -> Self {
+        Self {
+            bytes: AtomicU64::new(0),
+            files: AtomicU64::new(0),
+            jobs_done: AtomicUsize::new(0),
+            jobs_total: AtomicUsize::new(0),
+            started: Instant::now(),
+        }
+    }
+}
+
+impl Progress {
+    pub(crate) fn add(&self, files: u64, bytes: u64) {
+        self.files.fetch_add(files, Ordering::Relaxed);
+        self.bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    pub(crate) fn bytes(&self) -> u64 {
+        self.bytes.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn files(&self) -> u64 {
+        self.files.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn jobs(&self) -> (usize, usize) {
+        (
+            self.jobs_done.load(Ordering::Relaxed),
+            self.jobs_total.load(Ordering::Relaxed),
+        )
+    }
+
+    /// 0.0 ..= 1.0 of the jobs finished.
+    pub(crate) fn ratio(&self) -> f64 {
+        match self.jobs() {
+            (_, 0) => 1.0,
+            (done, total) => done as f64 / total as f64,
+        }
+    }
+
+    pub(crate) fn bytes_per_sec(&self) -> f64 {
+        self.bytes() as f64 / self.started.elapsed().as_secs_f64().max(0.001)
+    }
+
+    pub(crate) fn files_per_sec(&self) -> f64 {
+        self.files() as f64 / self.started.elapsed().as_secs_f64().max(0.001)
+    }
+}
+
+/// Run `work` over `jobs` on a pool of `threads` workers.
+///
+/// `done` gets each job with its result on the calling thread as soon as it
+/// finishes, and `tick` is called there regularly to draw progress.
+pub(crate) fn run<J, R>(
+    jobs: Vec<J>,
+    threads: usize,
+    progress: &Progress,
+    work: impl Fn(&J) -> R + Sync,
+    mut done: impl FnMut(J, R),
+    mut tick: impl FnMut(),
+) -> io::Result<()>
+where
+    J: Send,
+    R: Send,
+{
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .map_err(io::Error::other)?;
+    progress.jobs_total.fetch_add(jobs.len(), Ordering::Relaxed);
+    let (tx, rx) = mpsc::channel();
+
+    thread::scope(|s| {
+        s.spawn(|| {
+            pool.install(|| {
+                jobs.into_par_iter().for_each_with(tx, |tx, job| {
+                    let result = work(&job);
+                    let _ = tx.send((job, result));
+                })
+            })
+        });
+
+        loop {
+            match rx.recv_timeout(TICK) {
+                Ok((job, result)) => {
+                    progress.jobs_done.fetch_add(1, Ordering::Relaxed);
+                    done(job, result);
+                    tick();
+                }
+                Err(RecvTimeoutError::Timeout) => tick(),
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Like `fs::remove_dir_all`, but unlinks files in parallel on the current
+/// pool and removes each directory once its children are gone (deepest first).
+/// Returns the bytes freed.
+pub(crate) fn remove_dir_all(path: &Path, progress: &Progress) -> io::Result<u64> {
+    // a lone file, eg: a stale unit swept out of `target/debug/deps`
+    let metadata = fs::symlink_metadata(path)?;
+    if !metadata.is_dir() {
+        fs::remove_file(path)?;
+        progress.add(1, metadata.len());
+        return Ok(metadata.len());
+    }
+
+    let entries = fs::read_dir(path)?.collect::<io::Result<Vec<_>>>()?;
+
+    let bytes = entries
+        .par_iter()
+        .map(|entry| {
+            let entry_path = entry.path();
+            // file_type does not follow symlinks, a linked dir is unlinked not walked
+            if entry.file_type()?.is_dir() {
+                remove_dir_all(&entry_path, progress)
+            } else {
+                let len = entry.metadata().map(|m| m.len()).unwrap_or(0);
+                fs::remove_file(&entry_path)?;
+                progress.add(1, len);
+                Ok(len)
+            }
+        })
+        .collect::<Vec<_>>()
+        .into_iter()
+        .sum::<io::Result<u64>>()?;
+
+    fs::remove_dir(path)?;
+    Ok(bytes)
+}
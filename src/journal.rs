@@ -0,0 +1,128 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, Write},
+    path::PathBuf,
+    sync::Mutex,
+};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    output::{Record, RecordKind},
+    paths,
+};
+
+/// How a removal was agreed to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ConfirmMode {
+    /// `--force`, nobody was asked
+    Force,
+    /// confirmed by the user
+    Confirm,
+    /// `--ci` run
+    Ci,
+}
+
+/// One line of `<state dir>/journal.jsonl`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct JournalEntry {
+    pub(crate) timestamp: DateTime<Local>,
+    pub(crate) user: String,
+    pub(crate) path: PathBuf,
+    pub(crate) project: String,
+    pub(crate) cate: String,
+    /// bytes freed, 0 for a failed removal even if part of it went
+    pub(crate) size: u64,
+    pub(crate) result: RecordKind,
+    pub(crate) mode: ConfirmMode,
+    pub(crate) error: Option<String>,
+}
+
+impl JournalEntry {
+    /// Whether the space is actually gone from the project, a purge only
+    /// deletes what its quarantine already took out.
+    pub(crate) fn is_freed(&self) -> bool {
+        matches!(
+            self.result,
+            RecordKind::Removed | RecordKind::Trashed | RecordKind::Quarantined
+        )
+    }
+
+    /// Whether the space came back, eg: an undone quarantine.
+    pub(crate) fn is_restored(&self) -> bool {
+        self.result == RecordKind::Restored
+    }
+}
+
+fn journal_path() -> Option<PathBuf> {
+    paths::state_dir().map(|dir| dir.join("journal.jsonl"))
+}
+
+/// Append-only writer, a journal that failed to open silently records nothing.
+pub(crate) struct Journal {
+    file: Option<Mutex<File>>,
+    mode: ConfirmMode,
+    user: String,
+}
+
+impl Journal {
+    pub(crate) fn open(mode: ConfirmMode) -> Self {
+        let file = journal_path().and_then(|path| {
+            fs::create_dir_all(path.parent()?).ok()?;
+            let file = OpenOptions::new().create(true).append(true).open(&path);
+            if let Err(err) = &file {
+                eprintln!("[RM] Open journal {path:?} Error: {err}");
+            }
+            file.ok().map(Mutex::new)
+        });
+        let user = std::env::var("USER")
+            .or_else(|_| std::env::var("USERNAME"))
+            .unwrap_or_default();
+        Self { file, mode, user }
+    }
+
+    /// Record the outcome of a removal, restore or purge, scan and plan records are ignored.
+    pub(crate) fn append(&self, record: &Record) {
+        let Some(file) = &self.file else {
+            return;
+        };
+        if matches!(
+            record.kind,
+            RecordKind::Scan | RecordKind::Plan | RecordKind::Skipped
+        ) {
+            return;
+        }
+        let entry = JournalEntry {
+            timestamp: Local::now(),
+            user: self.user.clone(),
+            path: record.path.clone(),
+            project: record.project.clone(),
+            cate: record.cate.clone(),
+            size: record.size,
+            result: record.kind,
+            mode: self.mode,
+            error: record.error.clone(),
+        };
+        if let Ok(mut line) = serde_json::to_vec(&entry) {
+            line.push(b'\n');
+            // one write per line keeps concurrent appends from interleaving
+            let _ = file.lock().unwrap().write_all(&line);
+        }
+    }
+}
+
+/// Every entry of the journal, oldest first, skipping lines that do not parse.
+pub(crate) fn read() -> io::Result<Vec<JournalEntry>> {
+    let Some(path) = journal_path().filter(|path| path.exists()) else {
+        return Ok(vec![]);
+    };
+    let mut entries = Vec::new();
+    for line in BufReader::new(File::open(path)?).lines() {
+        if let Ok(entry) = serde_json::from_str(&line?) {
+            entries.push(entry);
+        }
+    }
+    Ok(entries)
+}
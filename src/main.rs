@@ -1,19 +1,57 @@
+use std::process;
+
 use clap::Parser;
 use command::Cli;
 use tokio::runtime::Builder;
 
+mod cachedir;
+mod cargo;
 mod command;
+mod config;
+mod engine;
+mod journal;
+mod output;
+mod paths;
+mod quarantine;
 mod scan_category;
 mod signal;
+mod size;
+#[cfg(test)]
+mod testing;
+mod trash;
 mod ui;
 
 async fn run() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
+    config::load()?;
+    scan_category::load(cli.rules.as_deref())?;
+    size::init(if cli.bytes {
+        size::Units::Bytes
+    } else if cli.si {
+        size::Units::Si
+    } else {
+        size::Units::Binary
+    });
 
     match cli.commands {
         command::Commands::Clear(args) => {
             args.run().await?;
         }
+        command::Commands::Scan(args) => {
+            args.run().await?;
+        }
+        command::Commands::Undo(args) => {
+            args.run().await?;
+        }
+        command::Commands::Restore(args) => {
+            args.run().await?;
+        }
+        command::Commands::Purge(args) => {
+            args.run().await?;
+        }
+        command::Commands::History(args) => {
+            args.run().await?;
+        }
     }
 
     Ok(())
@@ -26,6 +64,7 @@ fn main() {
         let r = run().await;
         if let Err(err) = r {
             eprintln!("Error: {}", err);
+            process::exit(1);
         }
     });
 }
@@ -0,0 +1,206 @@
+use std::{
+    io::{self, Stdout, Write},
+    path::PathBuf,
+    sync::Mutex,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{command::clear::ScanRow, size::format_size};
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// human readable lines
+    Table,
+    /// one JSON array printed at the end
+    Json,
+    /// one JSON object per line, streamed
+    Ndjson,
+    /// comma separated values with a header, streamed
+    Csv,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordKind {
+    /// a project found by the scan
+    Scan,
+    /// a directory the dry run would remove
+    Plan,
+    Removed,
+    /// moved to the system trash
+    Trashed,
+    /// moved to the quarantine, see `rmdev undo`
+    Quarantined,
+    Failed,
+    /// the user declined to remove it
+    Skipped,
+    /// moved back out of the quarantine, see `rmdev restore`
+    Restored,
+    /// deleted from the quarantine for good
+    Purged,
+}
+
+/// Every line of machine-readable output has this same shape, whatever its kind.
+#[derive(Debug, Clone, Serialize)]
+pub struct Record {
+    #[serde(rename = "type")]
+    pub kind: RecordKind,
+    pub path: PathBuf,
+    pub project: String,
+    pub cate: String,
+    pub size: u64, // Bytes
+    pub error: Option<String>,
+}
+
+impl Record {
+    pub fn new(kind: RecordKind, path: PathBuf, row: &ScanRow, size: u64) -> Self {
+        Self {
+            kind,
+            path,
+            project: row.project.clone(),
+            cate: row.cate.ident(),
+            size,
+            error: None,
+        }
+    }
+
+    pub fn with_error(mut self, error: impl ToString) -> Self {
+        self.error = Some(error.to_string());
+        self
+    }
+}
+
+impl From<&ScanRow> for Record {
+    fn from(row: &ScanRow) -> Self {
+        Self::new(RecordKind::Scan, row.path.clone(), row, row.size)
+    }
+}
+
+pub struct Output {
+    format: Format,
+    records: Mutex<Vec<Record>>,
+    csv: Mutex<csv::Writer<Stdout>>,
+    /// hold everything back until `finish`, eg: while the TUI owns the terminal
+    deferred: Option<Mutex<Vec<Pending>>>,
+}
+
+enum Pending {
+    Record(Record),
+    Note(String),
+}
+
+impl Output {
+    pub fn new(format: Format) -> Self {
+        Self {
+            format,
+            records: Mutex::new(Vec::new()),
+            csv: Mutex::new(csv::Writer::from_writer(io::stdout())),
+            deferred: None,
+        }
+    }
+
+    /// An output printing nothing before `finish`.
+    pub fn deferred(format: Format) -> Self {
+        Self {
+            deferred: Some(Mutex::new(Vec::new())),
+            ..Self::new(format)
+        }
+    }
+
+    pub fn emit(&self, record: Record) {
+        match &self.deferred {
+            Some(pending) => pending.lock().unwrap().push(Pending::Record(record)),
+            None => self.write(record),
+        }
+    }
+
+    /// A human readable summary line, only printed by `Format::Table`.
+    pub fn note(&self, note: impl Into<String>) {
+        if self.format != Format::Table {
+            return;
+        }
+        match &self.deferred {
+            Some(pending) => pending.lock().unwrap().push(Pending::Note(note.into())),
+            None => println!("{}", note.into()),
+        }
+    }
+
+    fn write(&self, record: Record) {
+        match self.format {
+            Format::Table => print_line(&record),
+            Format::Json => self.records.lock().unwrap().push(record),
+            Format::Ndjson => {
+                let mut stdout = io::stdout().lock();
+                let _ = serde_json::to_writer(&mut stdout, &record);
+                let _ = writeln!(stdout);
+            }
+            Format::Csv => {
+                let mut csv = self.csv.lock().unwrap();
+                let _ = csv.serialize(&record);
+                let _ = csv.flush();
+            }
+        }
+    }
+
+    /// Print whatever was held back, call once after the last `emit`.
+    pub fn finish(&self) -> io::Result<()> {
+        if let Some(pending) = &self.deferred {
+            for pending in pending.lock().unwrap().drain(..) {
+                match pending {
+                    Pending::Record(record) => self.write(record),
+                    Pending::Note(note) => println!("{note}"),
+                }
+            }
+        }
+        match self.format {
+            Format::Json => {
+                let records = self.records.lock().unwrap();
+                println!("{}", serde_json::to_string_pretty(&*records)?);
+            }
+            Format::Csv => self.csv.lock().unwrap().flush()?,
+            Format::Table | Format::Ndjson => {}
+        }
+        Ok(())
+    }
+}
+
+/// Print `items` in one of the machine-readable formats, `Format::Table` is left to the caller.
+pub fn print_all<T: Serialize>(format: Format, items: &[T]) -> io::Result<()> {
+    match format {
+        Format::Table => {}
+        Format::Json => println!("{}", serde_json::to_string_pretty(items)?),
+        Format::Ndjson => {
+            for item in items {
+                println!("{}", serde_json::to_string(item)?);
+            }
+        }
+        Format::Csv => {
+            let mut csv = csv::Writer::from_writer(io::stdout());
+            for item in items {
+                csv.serialize(item)?;
+            }
+            csv.flush()?;
+        }
+    }
+    Ok(())
+}
+
+fn print_line(record: &Record) {
+    let size = format_size(record.size);
+    match record.kind {
+        RecordKind::Scan => {}
+        RecordKind::Plan => println!("[PLAN] {size:>10}  {:<8}  {:?}", record.cate, record.path),
+        RecordKind::Removed => println!("[RM] {:?} success remove {size}.", record.path),
+        RecordKind::Trashed => println!("[RM] {:?} moved to trash {size}.", record.path),
+        RecordKind::Quarantined => println!("[RM] {:?} quarantined {size}.", record.path),
+        RecordKind::Failed => eprintln!(
+            "[RM] {:?} Error: {}",
+            record.path,
+            record.error.as_deref().unwrap_or_default()
+        ),
+        RecordKind::Skipped => println!("[RM] {:?} skipped.", record.path),
+        RecordKind::Restored => println!("[RM] {:?} restored {size}.", record.path),
+        RecordKind::Purged => println!("[RM] {:?} purged {size}.", record.path),
+    }
+}
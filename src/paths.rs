@@ -0,0 +1,33 @@
+use std::{env, path::PathBuf};
+
+const APP_NAME: &str = "rmdev";
+
+pub(crate) fn home_dir() -> Option<PathBuf> {
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .filter(|home| !home.is_empty())
+        .map(PathBuf::from)
+}
+
+/// `$XDG_<name>` if set, otherwise `~/<fallback>`.
+fn xdg_dir(name: &str, fallback: &str) -> Option<PathBuf> {
+    env::var_os(name)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home_dir().map(|home| home.join(fallback)))
+}
+
+/// eg: `~/.config/rmdev`
+pub(crate) fn config_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_CONFIG_HOME", ".config").map(|dir| dir.join(APP_NAME))
+}
+
+/// eg: `~/.local/share/Trash`
+pub(crate) fn trash_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_DATA_HOME", ".local/share").map(|dir| dir.join("Trash"))
+}
+
+/// eg: `~/.local/state/rmdev`
+pub(crate) fn state_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_STATE_HOME", ".local/state").map(|dir| dir.join(APP_NAME))
+}
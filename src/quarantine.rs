@@ -0,0 +1,236 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    journal::Journal,
+    output::{Record, RecordKind},
+    paths, trash,
+};
+
+/// Name of the staging directory created at the top of a scanned target.
+pub(crate) const STAGING_DIR: &str = ".rmdev-quarantine";
+
+/// What one `clear` run moved aside, kept as `<state dir>/quarantine/<id>.json`.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Manifest {
+    pub(crate) id: String,
+    pub(crate) created_at: DateTime<Local>,
+    /// where the entries of this run are staged
+    pub(crate) staging: PathBuf,
+    pub(crate) entries: Vec<Entry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Entry {
+    pub(crate) original: PathBuf,
+    pub(crate) staged: PathBuf,
+    pub(crate) project: String,
+    pub(crate) cate: String,
+    pub(crate) size: u64, // Bytes
+}
+
+impl Entry {
+    fn record(&self, kind: RecordKind) -> Record {
+        Record {
+            kind,
+            path: self.original.clone(),
+            project: self.project.clone(),
+            cate: self.cate.clone(),
+            size: self.size,
+            error: None,
+        }
+    }
+}
+
+fn manifest_dir() -> io::Result<PathBuf> {
+    paths::state_dir()
+        .map(|dir| dir.join("quarantine"))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no home directory for state"))
+}
+
+impl Manifest {
+    fn path(&self) -> io::Result<PathBuf> {
+        Ok(manifest_dir()?.join(format!("{}.json", self.id)))
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let path = self.path()?;
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(path, serde_json::to_vec_pretty(self)?)
+    }
+
+    /// Forget the run once nothing is left in it.
+    fn remove(&self) -> io::Result<()> {
+        if self.staging.exists() {
+            fs::remove_dir_all(&self.staging)?;
+        }
+        if let Some(parent) = self.staging.parent() {
+            // only goes away when no other run is staged next to it
+            let _ = fs::remove_dir(parent);
+        }
+        fs::remove_file(self.path()?)
+    }
+
+    pub(crate) fn size(&self) -> u64 {
+        self.entries.iter().map(|e| e.size).sum()
+    }
+
+    pub(crate) fn is_expired(&self, days: u64) -> bool {
+        Local::now() - self.created_at >= chrono::Duration::days(days as i64)
+    }
+
+    /// Move entries back to where they were, all of them or only those under `filter`.
+    pub(crate) fn restore(
+        &mut self,
+        filter: Option<&Path>,
+        journal: &Journal,
+    ) -> Vec<(PathBuf, io::Result<()>)> {
+        let mut results = Vec::new();
+        let mut kept = Vec::new();
+        for entry in self.entries.drain(..) {
+            if filter.is_some_and(|filter| !entry.original.starts_with(filter)) {
+                kept.push(entry);
+                continue;
+            }
+            let result = if entry.original.exists() {
+                Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    "original path exists again",
+                ))
+            } else {
+                trash::move_dir(&entry.staged, &entry.original)
+            };
+            match result {
+                Ok(()) => journal.append(&entry.record(RecordKind::Restored)),
+                Err(_) => kept.push(entry.clone()),
+            }
+            results.push((entry.original, result));
+        }
+        self.entries = kept;
+
+        let saved = if self.entries.is_empty() {
+            self.remove()
+        } else {
+            self.save()
+        };
+        if let Err(err) = saved {
+            results.push((self.staging.clone(), Err(err)));
+        }
+        results
+    }
+
+    /// Delete everything staged by this run for good.
+    pub(crate) fn purge(self, journal: &Journal) -> io::Result<u64> {
+        let size = self.size();
+        self.remove()?;
+        for entry in &self.entries {
+            journal.append(&entry.record(RecordKind::Purged));
+        }
+        Ok(size)
+    }
+}
+
+/// Every quarantined run, oldest first.
+pub(crate) fn runs() -> io::Result<Vec<Manifest>> {
+    let dir = manifest_dir()?;
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+    let mut runs = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            let content = fs::read(&path)?;
+            let manifest: Manifest = serde_json::from_slice(&content)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            runs.push(manifest);
+        }
+    }
+    runs.sort_by_key(|run| run.created_at);
+    Ok(runs)
+}
+
+/// Purge the runs older than `days`, returning how many bytes were freed.
+pub(crate) fn purge_expired(days: u64, journal: &Journal) -> io::Result<u64> {
+    let mut freed = 0;
+    for run in runs()? {
+        if run.is_expired(days) {
+            freed += run.purge(journal)?;
+        }
+    }
+    Ok(freed)
+}
+
+/// The run of a `clear` in progress, entries are staged with a rename so it is instant.
+pub(crate) struct Run {
+    manifest: Mutex<Manifest>,
+}
+
+impl Run {
+    /// Start a run staging under `<target>/.rmdev-quarantine/<id>`.
+    pub(crate) fn begin(target: &Path) -> io::Result<Self> {
+        let now = Local::now();
+        let root = target.canonicalize()?.join(STAGING_DIR);
+        let base = now.format("%Y%m%d-%H%M%S").to_string();
+        let mut id = base.clone();
+        let mut n = 1;
+        while root.join(&id).exists() || manifest_dir()?.join(format!("{id}.json")).exists() {
+            n += 1;
+            id = format!("{base}-{n}");
+        }
+        let staging = root.join(&id);
+        fs::create_dir_all(&staging)?;
+
+        Ok(Self {
+            manifest: Mutex::new(Manifest {
+                id,
+                created_at: now,
+                staging,
+                entries: vec![],
+            }),
+        })
+    }
+
+    pub(crate) fn stage(
+        &self,
+        path: &Path,
+        project: &str,
+        cate: &str,
+        size: u64,
+    ) -> io::Result<PathBuf> {
+        let mut manifest = self.manifest.lock().unwrap();
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let staged = manifest
+            .staging
+            .join(format!("{}-{name}", manifest.entries.len() + 1));
+        trash::move_dir(path, &staged)?;
+        manifest.entries.push(Entry {
+            original: path.to_path_buf(),
+            staged: staged.clone(),
+            project: project.to_string(),
+            cate: cate.to_string(),
+            size,
+        });
+        manifest.save()?;
+        Ok(staged)
+    }
+
+    /// Drop the staging directory if nothing was staged.
+    pub(crate) fn finish(self) -> io::Result<Option<String>> {
+        let manifest = self.manifest.into_inner().unwrap();
+        if manifest.entries.is_empty() {
+            let _ = fs::remove_dir(&manifest.staging);
+            if let Some(parent) = manifest.staging.parent() {
+                let _ = fs::remove_dir(parent);
+            }
+            return Ok(None);
+        }
+        Ok(Some(manifest.id))
+    }
+}
@@ -1,36 +1,344 @@
-use std::path::Path;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
-use once_cell::sync::Lazy;
+use glob::{MatchOptions, Pattern};
+use once_cell::sync::OnceCell;
+use serde::Deserialize;
 
-pub(crate) static STRATEGY: Lazy<Vec<ScanCate>> =
-    Lazy::new(|| vec![ScanCate::Npm, ScanCate::Cargo]);
+use crate::{cachedir, cargo, paths};
 
-#[derive(Debug, Clone)]
-pub enum ScanCate {
-    Npm,
+const DEFAULT_RULES: &str = include_str!("rules.toml");
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    // like a shell, eg: sbt's `*.json` must not take Cargo's `.rustc_info.json`
+    require_literal_leading_dot: true,
+};
+
+static STRATEGY: OnceCell<Vec<ScanCate>> = OnceCell::new();
+
+/// The enabled categories, in the order they are tried.
+pub(crate) fn strategy() -> &'static [ScanCate] {
+    STRATEGY.get().expect("scan rules are not loaded")
+}
+
+/// Merge the built-in rules with `~/.config/rmdev/rules.toml` and `rules_file`,
+/// later files overriding earlier ones by `ident`. Must run before the first scan.
+pub(crate) fn load(rules_file: Option<&Path>) -> anyhow::Result<()> {
+    let user_file = paths::config_dir()
+        .map(|dir| dir.join("rules.toml"))
+        .filter(|file| file.exists());
+    load_files(user_file.as_deref().into_iter().chain(rules_file))
+}
+
+/// Merge the built-in rules with `files` alone, in that order.
+pub(crate) fn load_files<'a>(files: impl IntoIterator<Item = &'a Path>) -> anyhow::Result<()> {
+    let mut rules = Vec::new();
+    merge_rules(&mut rules, parse_rules(DEFAULT_RULES)?);
+    for file in files {
+        merge_rules(&mut rules, read_rules(file)?);
+    }
+
+    let strategy = compile(rules)?;
+    STRATEGY
+        .set(strategy)
+        .map_err(|_| anyhow::anyhow!("scan rules are already loaded"))
+}
+
+#[derive(Debug, Deserialize)]
+struct RuleFile {
+    #[serde(default)]
+    cate: Vec<Rule>,
+}
+
+/// One `[[cate]]` entry of a rules file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Rule {
+    ident: String,
+    /// a directory containing any of these files is a project, eg: `package.json`
+    #[serde(default)]
+    markers: Vec<String>,
+    /// directories relative to the project that can be removed, eg: `node_modules`
+    #[serde(default)]
+    artifacts: Vec<String>,
+    /// an artifact is only this category's if it contains any of these, eg: `CACHEDIR.TAG`
+    #[serde(default)]
+    signatures: Vec<String>,
+    #[serde(default)]
+    conditions: Conditions,
+    #[serde(default)]
+    layout: Layout,
+    #[serde(default = "enabled_default")]
+    enabled: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Conditions {
+    /// every one of these must also exist in the project
+    requires: Vec<String>,
+    /// none of these may exist in the project
+    excludes: Vec<String>,
+}
+
+/// How the artifacts of a project are found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Layout {
+    /// the `artifacts` globs under the project
+    #[default]
+    Plain,
+    /// one row per workspace, at the target directory Cargo would build into
     Cargo,
+    /// the project is itself the artifact, a directory with a valid `CACHEDIR.TAG`
+    Cachedir,
+}
+
+fn enabled_default() -> bool {
+    true
+}
+
+fn parse_rules(content: &str) -> anyhow::Result<Vec<Rule>> {
+    Ok(toml::from_str::<RuleFile>(content)?.cate)
+}
+
+fn read_rules(path: &Path) -> anyhow::Result<Vec<Rule>> {
+    let content = fs::read_to_string(path)
+        .map_err(|err| anyhow::anyhow!("read rules file {path:?}: {err}"))?;
+    parse_rules(&content).map_err(|err| anyhow::anyhow!("parse rules file {path:?}: {err}"))
+}
+
+fn merge_rules(rules: &mut Vec<Rule>, overrides: Vec<Rule>) {
+    for rule in overrides {
+        match rules
+            .iter_mut()
+            .find(|r| r.ident.eq_ignore_ascii_case(&rule.ident))
+        {
+            Some(r) => *r = rule,
+            None => rules.push(rule),
+        }
+    }
+}
+
+fn compile(rules: Vec<Rule>) -> anyhow::Result<Vec<ScanCate>> {
+    rules
+        .into_iter()
+        .filter(|rule| rule.enabled)
+        .map(|rule| {
+            let patterns = |list: &[String]| {
+                list.iter()
+                    .map(|p| Pattern::new(p))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|err| anyhow::anyhow!("category {:?}: {err}", rule.ident))
+            };
+            Ok(ScanCate {
+                markers: patterns(&rule.markers)?,
+                artifacts: patterns(&rule.artifacts)?,
+                signatures: patterns(&rule.signatures)?,
+                requires: patterns(&rule.conditions.requires)?,
+                excludes: patterns(&rule.conditions.excludes)?,
+                layout: rule.layout,
+                ident: rule.ident,
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct ScanCate {
+    ident: String,
+    markers: Vec<Pattern>,
+    artifacts: Vec<Pattern>,
+    signatures: Vec<Pattern>,
+    requires: Vec<Pattern>,
+    excludes: Vec<Pattern>,
+    layout: Layout,
 }
 
 impl ScanCate {
     pub(crate) fn access_keyfile(&self, path: &Path) -> bool {
-        match self {
-            Self::Npm => path.join("package.json").exists(),
-            Self::Cargo => path.join("Cargo.toml").exists(),
+        self.markers.iter().any(|p| dir_contains(path, p))
+            && self.requires.iter().all(|p| dir_contains(path, p))
+            && !self.excludes.iter().any(|p| dir_contains(path, p))
+    }
+
+    /// `path` is removable only when it is one of the artifact directories of
+    /// the project `root`, eg: `<root>/target`.
+    pub(crate) fn rm_keyfile(&self, root: &Path, path: &Path) -> bool {
+        match path.strip_prefix(root) {
+            Ok(rel) if !rel.as_os_str().is_empty() => self
+                .artifacts
+                .iter()
+                .any(|p| p.matches_path_with(rel, MATCH_OPTIONS)),
+            _ => false,
         }
     }
 
-    pub(crate) fn rm_keyfile(&self, path: &Path) -> bool {
-        match self {
-            Self::Npm => path.to_str().unwrap().contains("node_modules"),
-            Self::Cargo => path.to_str().unwrap().contains("target"),
+    /// whether `path` is the artifact directory of a project of this category.
+    pub(crate) fn is_artifact(&self, path: &Path) -> bool {
+        if self.layout == Layout::Cachedir {
+            return self.is_cache_dir(path);
         }
+        self.owns(path)
+            && path
+                .ancestors()
+                .skip(1)
+                .take(self.artifact_depth())
+                .any(|root| self.rm_keyfile(root, path) && self.access_keyfile(root))
+    }
+
+    /// whether the contents of the artifact directory `path` show this category made it,
+    /// eg: a `target` with `.rustc_info.json` is Cargo's and one with `maven-status` Maven's.
+    pub(crate) fn owns(&self, path: &Path) -> bool {
+        self.signatures.is_empty() || self.signatures.iter().any(|p| dir_contains(path, p))
+    }
+
+    /// whether `path` has a valid `CACHEDIR.TAG` and no other category claims it,
+    /// eg: Cargo tags its `target` too, which `--cargo` may only partly remove.
+    pub(crate) fn is_cache_dir(&self, path: &Path) -> bool {
+        self.access_keyfile(path)
+            && cachedir::tag(path).is_some()
+            && self.owns(path)
+            && !strategy().iter().any(|other| {
+                other.ident != self.ident
+                    && match other.layout {
+                        Layout::Plain => other.is_artifact(path),
+                        Layout::Cargo => {
+                            other.is_artifact(path) || cargo::is_target_dir(path, other)
+                        }
+                        Layout::Cachedir => false,
+                    }
+            })
+    }
+
+    /// how many levels below the project root an artifact can be.
+    pub(crate) fn artifact_depth(&self) -> usize {
+        self.artifacts
+            .iter()
+            .map(|p| Path::new(p.as_str()).components().count())
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub(crate) fn layout(&self) -> Layout {
+        self.layout
     }
 
     pub(crate) fn ident(&self) -> String {
-        match self {
-            Self::Npm => "NPM",
-            Self::Cargo => "Cargo",
+        self.ident.clone()
+    }
+}
+
+fn is_literal(pattern: &Pattern) -> bool {
+    !pattern.as_str().contains(['*', '?', '['])
+}
+
+fn dir_contains(dir: &Path, pattern: &Pattern) -> bool {
+    if is_literal(pattern) {
+        return dir.join(pattern.as_str()).exists();
+    }
+    let Ok(entries) = fs::read_dir(dir) else {
+        return false;
+    };
+    entries
+        .flatten()
+        .any(|entry| pattern.matches_path_with(&PathBuf::from(entry.file_name()), MATCH_OPTIONS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+
+    fn cate(rules: &str) -> ScanCate {
+        compile(parse_rules(rules).unwrap()).unwrap().remove(0)
+    }
+
+    /// A built-in category, without the user's rules.
+    fn builtin(ident: &str) -> ScanCate {
+        compile(parse_rules(DEFAULT_RULES).unwrap())
+            .unwrap()
+            .into_iter()
+            .find(|cate| cate.ident == ident)
+            .unwrap()
+    }
+
+    fn cargo() -> ScanCate {
+        cate(
+            r#"
+            [[cate]]
+            ident = "Cargo"
+            markers = ["Cargo.toml"]
+            artifacts = ["target"]
+            "#,
+        )
+    }
+
+    #[test]
+    fn matches_whole_path_components_only() {
+        let npm = cate(
+            r#"
+            [[cate]]
+            ident = "NPM"
+            markers = ["package.json"]
+            artifacts = ["node_modules"]
+            "#,
+        );
+        let (cargo, root) = (cargo(), Path::new("/p"));
+        assert!(cargo.rm_keyfile(root, Path::new("/p/target")));
+        assert!(npm.rm_keyfile(root, Path::new("/p/node_modules")));
+        for path in [
+            "/p",
+            "/p/src/targeting",
+            "/p/my_targets",
+            "/p/src/target",
+            "/q/target",
+        ] {
+            assert!(!cargo.rm_keyfile(root, Path::new(path)), "{path}");
         }
-        .to_string()
+        for path in ["/p/node_modules_backup", "/p/old_node_modules"] {
+            assert!(!npm.rm_keyfile(root, Path::new(path)), "{path}");
+        }
+    }
+
+    #[test]
+    fn artifacts_belong_to_a_project() {
+        let dir = TempDir::new("is-artifact");
+        dir.dirs(&["app/target", "app/src/targeting", "loose/target"])
+            .file("app/Cargo.toml", "");
+        let cargo = cargo();
+        assert!(cargo.is_artifact(&dir.path().join("app/target")));
+        assert!(!cargo.is_artifact(&dir.path().join("app/src/targeting")));
+        assert!(!cargo.is_artifact(&dir.path().join("loose/target")));
+    }
+
+    #[test]
+    fn signatures_tell_who_built_a_target() {
+        let dir = TempDir::new("owns");
+        dir.dirs(&[
+            "java/target/classes",
+            "rust/target/debug",
+            "scala/target/scala-2.13",
+            "empty/target",
+        ])
+        .file("rust/target/.rustc_info.json", "{}");
+        let [cargo, maven, sbt, npm] = ["Cargo", "Maven", "sbt", "NPM"].map(builtin);
+        let target = |project: &str| dir.path().join(project).join("target");
+
+        assert!(maven.owns(&target("java")));
+        assert!(!cargo.owns(&target("java")));
+        assert!(cargo.owns(&target("rust")));
+        assert!(!maven.owns(&target("rust")) && !sbt.owns(&target("rust")));
+        assert!(sbt.owns(&target("scala")));
+        assert!(!cargo.owns(&target("scala")) && !maven.owns(&target("scala")));
+        // nobody claims an empty target, a category without signatures takes anything
+        assert!([&cargo, &maven, &sbt]
+            .iter()
+            .all(|cate| !cate.owns(&target("empty"))));
+        assert!(npm.owns(&target("empty")));
     }
 }
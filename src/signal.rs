@@ -1,4 +1,11 @@
-use std::sync::atomic::AtomicUsize;
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{atomic::AtomicUsize, Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::{command::clear::RowKey, engine::Progress};
 
 #[derive(Debug, Default)]
 pub struct Signal {
@@ -20,3 +27,120 @@ impl Signal {
         self.code.load(std::sync::atomic::Ordering::Relaxed)
     }
 }
+
+/// `ScanStatus::state` once the scan is over, it is 0 while scanning.
+pub const SCAN_DONE: usize = 1;
+
+/// Shared between `scan_target` and whoever wants to watch it, eg: the TUI header.
+#[derive(Debug)]
+pub struct ScanStatus {
+    pub state: Signal,
+    visited: AtomicUsize,
+    current: Mutex<PathBuf>,
+    started: Instant,
+    elapsed: Mutex<Option<Duration>>,
+}
+
+impl ScanStatus {
+    pub fn new() -> Self {
+        ScanStatus {
+            state: Signal::new(),
+            visited: AtomicUsize::new(0),
+            current: Mutex::new(PathBuf::new()),
+            started: Instant::now(),
+            elapsed: Mutex::new(None),
+        }
+    }
+
+    pub fn visit(&self, path: &std::path::Path) {
+        self.visited
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        path.clone_into(&mut self.current.lock().unwrap());
+    }
+
+    pub fn finish(&self) {
+        *self.elapsed.lock().unwrap() = Some(self.started.elapsed());
+        self.state.set(SCAN_DONE);
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.state.get() == SCAN_DONE
+    }
+
+    /// directories visited so far
+    pub fn visited(&self) -> usize {
+        self.visited.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    pub fn current(&self) -> PathBuf {
+        self.current.lock().unwrap().clone()
+    }
+
+    /// time spent scanning, frozen once finished
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+            .lock()
+            .unwrap()
+            .unwrap_or_else(|| self.started.elapsed())
+    }
+}
+
+/// `ClearStatus::state` codes, it is 0 until a clear starts.
+pub const CLEAR_RUNNING: usize = 1;
+pub const CLEAR_DONE: usize = 2;
+
+/// Where a row is in a clear run by the TUI.
+#[derive(Debug, Clone)]
+pub enum RowState {
+    Pending,
+    Deleting,
+    /// bytes freed
+    Removed(u64),
+    /// bytes freed before the error
+    Failed(u64, String),
+    /// bytes a dry run would free, nothing was removed
+    Planned(u64),
+}
+
+/// Shared between `clear_target` and the TUI drawing its progress.
+#[derive(Debug, Default)]
+pub struct ClearStatus {
+    pub state: Signal,
+    pub progress: Arc<Progress>,
+    rows: Mutex<HashMap<RowKey, RowState>>,
+    /// why the clear stopped before or between rows, eg: the quarantine could not start
+    error: Mutex<Option<String>>,
+}
+
+impl ClearStatus {
+    pub fn is_running(&self) -> bool {
+        self.state.get() == CLEAR_RUNNING
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.state.get() == CLEAR_DONE
+    }
+
+    pub fn set_row(&self, key: RowKey, state: RowState) {
+        self.rows.lock().unwrap().insert(key, state);
+    }
+
+    pub fn row(&self, key: &RowKey) -> Option<RowState> {
+        self.rows.lock().unwrap().get(key).cloned()
+    }
+
+    pub fn rows(&self) -> Vec<(RowKey, RowState)> {
+        let rows = self.rows.lock().unwrap();
+        rows.iter()
+            .map(|(key, state)| (key.clone(), state.clone()))
+            .collect()
+    }
+
+    pub fn fail(&self, err: impl ToString) {
+        *self.error.lock().unwrap() = Some(err.to_string());
+    }
+
+    pub fn error(&self) -> Option<String> {
+        self.error.lock().unwrap().clone()
+    }
+}
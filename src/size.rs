@@ -0,0 +1,81 @@
+use once_cell::sync::OnceCell;
+
+static UNITS: OnceCell<Units> = OnceCell::new();
+
+const BINARY: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+const SI: [&str; 5] = ["B", "kB", "MB", "GB", "TB"];
+
+/// How sizes are printed everywhere but JSON and CSV, which always carry raw bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum Units {
+    /// powers of 1024: KiB, MiB, GiB
+    #[default]
+    Binary,
+    /// `--si`, powers of 1000: kB, MB, GB
+    Si,
+    /// `--bytes`, the plain byte count
+    Bytes,
+}
+
+pub(crate) fn init(units: Units) {
+    let _ = UNITS.set(units);
+}
+
+fn units() -> Units {
+    UNITS.get().copied().unwrap_or_default()
+}
+
+/// eg: `512B`, `40.00KiB`, `1.50GiB`, or `1610612736` with `--bytes`.
+pub(crate) fn format_size(bytes: u64) -> String {
+    format_in(units(), bytes)
+}
+
+fn format_in(units: Units, bytes: u64) -> String {
+    let (base, names) = match units {
+        Units::Bytes => return bytes.to_string(),
+        Units::Binary => (1024.0, BINARY),
+        Units::Si => (1000.0, SI),
+    };
+    if (bytes as f64) < base {
+        return format!("{bytes}{}", names[0]);
+    }
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    // by what it rounds to, 1048575 bytes is `1.00MiB` rather than `1024.00KiB`
+    while (value * 100.0).round() / 100.0 >= base && unit < names.len() - 1 {
+        value /= base;
+        unit += 1;
+    }
+    format!("{value:.2}{}", names[unit])
+}
+
+/// eg: `12.50MiB/s`.
+pub(crate) fn format_rate(bytes_per_sec: f64) -> String {
+    format!("{}/s", format_size(bytes_per_sec as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_at_unit_boundaries() {
+        let binary = |bytes| format_in(Units::Binary, bytes);
+        assert_eq!(binary(0), "0B");
+        assert_eq!(binary(1023), "1023B");
+        assert_eq!(binary(1024), "1.00KiB");
+        assert_eq!(binary(1536), "1.50KiB");
+        assert_eq!(binary(1024 * 1024 - 1), "1.00MiB");
+        assert_eq!(binary(1024 * 1024), "1.00MiB");
+        assert_eq!(binary(1 << 40), "1.00TiB");
+        assert_eq!(binary(1 << 50), "1024.00TiB");
+
+        let si = |bytes| format_in(Units::Si, bytes);
+        assert_eq!(si(999), "999B");
+        assert_eq!(si(1000), "1.00kB");
+        assert_eq!(si(999_999), "1.00MB");
+        assert_eq!(si(1_500_000_000), "1.50GB");
+
+        assert_eq!(format_in(Units::Bytes, 1_610_612_736), "1610612736");
+    }
+}
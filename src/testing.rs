@@ -0,0 +1,64 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::cachedir;
+
+static NEXT: AtomicUsize = AtomicUsize::new(0);
+
+/// A directory under the system temp dir for one test, removed on drop even if it fails.
+pub(crate) struct TempDir(PathBuf);
+
+impl TempDir {
+    pub(crate) fn new(name: &str) -> Self {
+        let dir = env::temp_dir().join(format!(
+            "rmdev-{name}-{}-{}",
+            process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        // canonical so it compares equal to what the scan reports, eg: on macOS /tmp is a link
+        Self(dir.canonicalize().unwrap())
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.0
+    }
+
+    /// Create the directories `rels`, eg: `target/debug/incremental`.
+    pub(crate) fn dirs(&self, rels: &[&str]) -> &Self {
+        for rel in rels {
+            fs::create_dir_all(self.0.join(rel)).unwrap();
+        }
+        self
+    }
+
+    /// Write `content` to `rel`, creating its parent directories.
+    pub(crate) fn file(&self, rel: &str, content: &str) -> &Self {
+        let path = self.0.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+        self
+    }
+
+    /// Tag `rel` as a cache directory `tool` created, the way Cargo tags its `target`.
+    pub(crate) fn tag(&self, rel: &str, tool: &str) -> &Self {
+        self.file(
+            &format!("{rel}/CACHEDIR.TAG"),
+            &format!(
+                "{}\n# This file is a cache directory tag created by {tool}.\n",
+                cachedir::SIGNATURE
+            ),
+        )
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
@@ -0,0 +1,179 @@
+use std::{
+    env,
+    ffi::{OsStr, OsString},
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::paths;
+
+/// Move `path` into `~/.local/share/Trash/files` and return where it ended up,
+/// following <https://specifications.freedesktop.org/trash-spec/trashspec-latest.html>
+/// so file managers can restore it.
+pub(crate) fn move_to_trash(path: &Path) -> io::Result<PathBuf> {
+    let trash = paths::trash_dir()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no home directory for trash"))?;
+    trash_into(&trash, path)
+}
+
+fn trash_into(trash: &Path, path: &Path) -> io::Result<PathBuf> {
+    let files_dir = trash.join("files");
+    let info_dir = trash.join("info");
+    fs::create_dir_all(&files_dir)?;
+    fs::create_dir_all(&info_dir)?;
+
+    let name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "can not trash a root"))?;
+    // only the parent is resolved, a symlinked artifact is trashed as the link, not its target
+    let path = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.canonicalize()?,
+        _ => env::current_dir()?,
+    }
+    .join(name);
+
+    // the spec reserves a name by creating its .trashinfo exclusively first
+    let (trashed, info_path) = reserve_name(&files_dir, &info_dir, name)?;
+    let result = write_info(&info_path, &path).and_then(|_| move_dir(&path, &trashed));
+    if let Err(err) = result {
+        let _ = fs::remove_file(&info_path);
+        return Err(err);
+    }
+
+    Ok(trashed)
+}
+
+fn reserve_name(files_dir: &Path, info_dir: &Path, name: &OsStr) -> io::Result<(PathBuf, PathBuf)> {
+    for i in 1.. {
+        let mut candidate = OsString::from(name);
+        if i > 1 {
+            candidate.push(format!(".{i}"));
+        }
+        let mut info_name = candidate.clone();
+        info_name.push(".trashinfo");
+
+        let trashed = files_dir.join(&candidate);
+        let info_path = info_dir.join(info_name);
+        if trashed.symlink_metadata().is_ok() {
+            continue;
+        }
+        match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&info_path)
+        {
+            Ok(_) => return Ok((trashed, info_path)),
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(err) => return Err(err),
+        }
+    }
+    unreachable!()
+}
+
+fn write_info(info_path: &Path, original: &Path) -> io::Result<()> {
+    let mut file = OpenOptions::new().write(true).open(info_path)?;
+    write!(
+        file,
+        "[Trash Info]\nPath={}\nDeletionDate={}\n",
+        encode_path(original),
+        chrono::Local::now().format("%Y-%m-%dT%H:%M:%S")
+    )
+}
+
+/// Percent-encode everything but unreserved characters and `/`, as the spec asks.
+fn encode_path(path: &Path) -> String {
+    let mut encoded = String::new();
+    for byte in path.as_os_str().as_encoded_bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                encoded.push(*byte as char)
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+/// `rename`, falling back to copy and delete when `to` is on another filesystem.
+pub(crate) fn move_dir(from: &Path, to: &Path) -> io::Result<()> {
+    match fs::rename(from, to) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == io::ErrorKind::CrossesDevices => {
+            let file_type = fs::symlink_metadata(from)?.file_type();
+            if file_type.is_symlink() {
+                copy_symlink(from, to)?;
+                return fs::remove_file(from);
+            }
+            if file_type.is_file() {
+                fs::copy(from, to)?;
+                return fs::remove_file(from);
+            }
+            if let Err(err) = copy_dir(from, to) {
+                let _ = fs::remove_dir_all(to);
+                return Err(err);
+            }
+            fs::remove_dir_all(from)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    let mut stack = vec![(from.to_path_buf(), to.to_path_buf())];
+
+    while let Some((from, to)) = stack.pop() {
+        fs::create_dir(&to)?;
+        for entry in fs::read_dir(&from)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            let target = to.join(entry.file_name());
+            if file_type.is_dir() {
+                stack.push((entry.path(), target));
+            } else if file_type.is_symlink() {
+                copy_symlink(&entry.path(), &target)?;
+            } else {
+                fs::copy(entry.path(), target)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(from)?, to)
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+    fs::copy(from, to).map(|_| ())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+
+    #[test]
+    fn trashes_a_symlinked_artifact_not_its_target() {
+        let dir = TempDir::new("trash-symlink");
+        dir.file("real/node_modules/left-pad/index.js", "")
+            .dirs(&["app", "trash"]);
+        let link = dir.path().join("app/node_modules");
+        std::os::unix::fs::symlink(dir.path().join("real/node_modules"), &link).unwrap();
+
+        let trashed = trash_into(&dir.path().join("trash"), &link).unwrap();
+        assert!(trashed.symlink_metadata().unwrap().file_type().is_symlink());
+        assert!(link.symlink_metadata().is_err());
+        assert!(dir
+            .path()
+            .join("real/node_modules/left-pad/index.js")
+            .is_file());
+
+        let info =
+            fs::read_to_string(dir.path().join("trash/info/node_modules.trashinfo")).unwrap();
+        assert!(info.contains(&format!("Path={}\n", encode_path(&link))));
+    }
+}
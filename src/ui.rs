@@ -1,14 +1,18 @@
 use std::{
+    collections::HashSet,
     error::Error,
     io,
-    sync::{Arc, Mutex},
+    sync::{mpsc::Sender, Arc, Mutex},
     time::{Duration, Instant},
 };
 
 use ratatui::{
     crossterm::{
         self,
-        event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind},
+        event::{
+            self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind,
+            MouseButton, MouseEvent, MouseEventKind,
+        },
         execute,
         terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
     },
@@ -16,10 +20,26 @@ use ratatui::{
     widgets::*,
 };
 use style::palette::tailwind;
-use tokio::time::interval;
-use unicode_width::UnicodeWidthStr;
 
-use crate::command::clear::ScanRow;
+use crate::{
+    cargo::Clean,
+    command::clear::{RowKey, ScanRow},
+    journal::{self, JournalEntry},
+    signal::{ClearStatus, RowState, ScanStatus},
+    size::{format_rate, format_size},
+};
+
+mod confirm;
+mod detail;
+mod filter;
+mod router;
+mod tabs;
+
+use confirm::{Answer, Confirm};
+use detail::Detail;
+use filter::Filter;
+use router::{EventType, Route, RouteType, Router};
+use tabs::Caches;
 
 const PALETTES: [tailwind::Palette; 4] = [
     tailwind::RED,
@@ -27,7 +47,61 @@ const PALETTES: [tailwind::Palette; 4] = [
     tailwind::EMERALD,
     tailwind::INDIGO,
 ];
-const INFO_TEXT: &str = "(Esc) quit | (↑) move up | (↓) move down | (Enter) clear all cache";
+/// Footer help, a click on an entry with a key presses it.
+type Actions = &'static [(&'static str, Option<KeyCode>)];
+
+const INFO_ACTIONS: Actions = &[
+    ("(Esc) quit", Some(KeyCode::Esc)),
+    ("(↑/↓) move", None),
+    ("(Space) select", Some(KeyCode::Char(' '))),
+    ("(a) select all", Some(KeyCode::Char('a'))),
+    ("(i) invert", Some(KeyCode::Char('i'))),
+    ("(1-4) sort", None),
+    ("(/) filter", Some(KeyCode::Char('/'))),
+    ("(c) category", Some(KeyCode::Char('c'))),
+    ("(m) min size", Some(KeyCode::Char('m'))),
+    ("(→) details", Some(KeyCode::Right)),
+    ("(Tab) tabs", Some(KeyCode::Tab)),
+    ("(M) mouse", Some(KeyCode::Char('M'))),
+    ("(Enter) clear selected", Some(KeyCode::Enter)),
+];
+const TAB_ACTIONS: Actions = &[
+    ("(Esc) quit", Some(KeyCode::Esc)),
+    ("(↑/↓) move", None),
+    ("(Tab) next tab", Some(KeyCode::Tab)),
+    ("(t) theme", Some(KeyCode::Char('t'))),
+    ("(M) mouse", Some(KeyCode::Char('M'))),
+];
+const DETAIL_ACTIONS: Actions = &[
+    ("(Esc) quit", Some(KeyCode::Esc)),
+    ("(↑/↓) move", None),
+    ("(←) back", Some(KeyCode::Left)),
+    ("(t) theme", Some(KeyCode::Char('t'))),
+    ("(M) mouse", Some(KeyCode::Char('M'))),
+];
+
+/// Columns of the projects table: the checkbox, `ScanRow::ref_head` and the clear state.
+const COLUMNS: [Constraint; 6] = [
+    Constraint::Length(3),
+    Constraint::Percentage(25),
+    Constraint::Max(10),
+    Constraint::Max(10),
+    Constraint::Min(10),
+    Constraint::Max(30),
+];
+
+/// Width of the highlight symbol in front of the projects table.
+const BAR_WIDTH: u16 = 3;
+
+/// Lines taken by a row of the projects table.
+const ROW_HEIGHT: u16 = 3;
+
+const TITLE: &str = "  🚀 Rmdev (https://github.com/WumaCoder/rmdev 🌟)";
+
+const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// How long a notice replaces the key help in the footer.
+const NOTICE_TIME: Duration = Duration::from_secs(3);
 
 const ITEM_HEIGHT: usize = 4;
 
@@ -59,31 +133,110 @@ impl TableColors {
 
 pub struct UI {
     pub rows: Arc<Mutex<Vec<ScanRow>>>,
+    /// rows chosen for deletion, read back by the caller after exit
+    pub selected: Arc<Mutex<HashSet<RowKey>>>,
+    pub status: Arc<ScanStatus>,
+    pub clear: Arc<ClearStatus>,
+    /// asks the caller to clear the confirmed rows while the TUI stays open
+    pub request: Sender<HashSet<RowKey>>,
+    /// clear without asking for confirmation
+    pub force: bool,
+    /// only plan the clear, the TUI sums up what would go
+    pub dry_run: bool,
+    /// the part of Cargo target dirs being cleared
+    pub clean: Clean,
+}
+
+/// The column rows are ordered by, in the order of `ScanRow::ref_head`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SortKey {
+    Project,
+    Cate,
+    Size,
+    Path,
+}
+
+impl SortKey {
+    const ALL: [SortKey; 4] = [Self::Project, Self::Cate, Self::Size, Self::Path];
+
+    fn column(self) -> usize {
+        self as usize
+    }
+
+    fn compare(self, a: &ScanRow, b: &ScanRow) -> std::cmp::Ordering {
+        match self {
+            Self::Project => a.project.to_lowercase().cmp(&b.project.to_lowercase()),
+            Self::Cate => a.cate.ident().cmp(&b.cate.ident()),
+            Self::Size => a.size.cmp(&b.size),
+            Self::Path => a.path.cmp(&b.path),
+        }
+        // a fixed order for equal keys, rows keep arriving while scanning
+        .then_with(|| a.key().cmp(&b.key()))
+    }
 }
 
 struct App {
     state: TableState,
-    longest_item_lens: Vec<usize>,
     scroll_state: ScrollbarState,
     colors: TableColors,
     color_index: usize,
+    notice: Option<(String, Instant)>,
+    confirm: Option<Confirm>,
+    clear_requested: bool,
+    sort: SortKey,
+    descending: bool,
+    filter: Filter,
+    /// indices into the rows passing `filter`, in sort order, the cursor indexes this
+    view: Vec<usize>,
+    router: Router,
+    /// the screen pushed by `router` for the row under the cursor
+    detail: Option<Detail>,
+    /// cursor of the tabs besides Projects
+    tab_state: TableState,
+    caches: Option<Caches>,
+    history: Vec<JournalEntry>,
+    /// the terminal reports the mouse, off lets it select text instead
+    mouse: bool,
+    /// where the last frame drew the projects table, for clicks
+    table_area: Rect,
+    /// where the last frame drew each footer action
+    footer_hits: Vec<(Rect, KeyCode)>,
     ui: UI,
 }
 
 impl App {
     fn new(ui: UI) -> Self {
-        let scan_rows = ui.rows.clone();
         Self {
             state: TableState::default().with_selected(0),
-            longest_item_lens: constraint_len_calculator(scan_rows.clone()),
-            scroll_state: ScrollbarState::new(0 * ITEM_HEIGHT),
+            scroll_state: ScrollbarState::new(0),
             colors: TableColors::new(&PALETTES[0]),
             color_index: 0,
+            notice: None,
+            confirm: None,
+            clear_requested: false,
+            // the biggest wins on top
+            sort: SortKey::Size,
+            descending: true,
+            filter: Filter::default(),
+            view: Vec::new(),
+            router: Router::new(vec![
+                Route::new(RouteType::Push, "projects", "Projects"),
+                Route::new(RouteType::View, "categories", "Categories"),
+                Route::new(RouteType::View, "caches", "Global caches"),
+                Route::new(RouteType::View, "history", "History"),
+            ]),
+            detail: None,
+            tab_state: TableState::default().with_selected(0),
+            caches: None,
+            history: Vec::new(),
+            mouse: true,
+            table_area: Rect::default(),
+            footer_hits: Vec::new(),
             ui,
         }
     }
     pub fn next(&mut self) {
-        let len = self.ui.rows.lock().unwrap().len();
+        let len = self.view.len();
         if len == 0 {
             return;
         }
@@ -97,12 +250,11 @@ impl App {
             }
             None => 0,
         };
-        self.state.select(Some(i));
-        self.scroll_state = ScrollbarState::new((len - 1) * ITEM_HEIGHT).position(i * ITEM_HEIGHT);
+        self.select(i);
     }
 
     pub fn previous(&mut self) {
-        let len = self.ui.rows.lock().unwrap().len();
+        let len = self.view.len();
         if len == 0 {
             return;
         }
@@ -116,8 +268,337 @@ impl App {
             }
             None => 0,
         };
+        self.select(i);
+    }
+
+    /// Move the cursor to row `i` of the view.
+    fn select(&mut self, i: usize) {
         self.state.select(Some(i));
-        self.scroll_state = ScrollbarState::new((len - 1) * ITEM_HEIGHT).position(i * ITEM_HEIGHT);
+        self.scroll_state = ScrollbarState::new(self.view.len().saturating_sub(1) * ITEM_HEIGHT)
+            .position(i * ITEM_HEIGHT);
+    }
+
+    /// Sort by `key`, or flip the direction if already sorted by it.
+    fn sort_by(&mut self, key: SortKey) {
+        if self.sort == key {
+            self.descending = !self.descending;
+        } else {
+            self.sort = key;
+            // sizes read best largest first, names alphabetically
+            self.descending = key == SortKey::Size;
+        }
+    }
+
+    /// Keep the rows in order as the scan appends to them and rebuild the filtered view,
+    /// the cursor stays on its row.
+    fn refresh_view(&mut self) {
+        let mut rows = self.ui.rows.lock().unwrap();
+        let cursor = self
+            .state
+            .selected()
+            .and_then(|i| self.view.get(i))
+            .and_then(|&i| rows.get(i))
+            .map(ScanRow::key);
+        let (key, descending) = (self.sort, self.descending);
+        rows.sort_by(|a, b| {
+            let order = key.compare(a, b);
+            if descending {
+                order.reverse()
+            } else {
+                order
+            }
+        });
+        self.view = (0..rows.len())
+            .filter(|&i| self.filter.matches(&rows[i]))
+            .collect();
+        let i = cursor
+            .and_then(|cursor| self.view.iter().position(|&i| rows[i].key() == cursor))
+            .unwrap_or(0);
+        drop(rows);
+        self.select(i);
+    }
+
+    pub fn toggle_selected(&mut self) {
+        let Some(&i) = self.state.selected().and_then(|i| self.view.get(i)) else {
+            return;
+        };
+        let rows = self.ui.rows.lock().unwrap();
+        let Some(row) = rows.get(i) else {
+            return;
+        };
+        let mut selected = self.ui.selected.lock().unwrap();
+        if !selected.remove(&row.key()) {
+            selected.insert(row.key());
+        }
+    }
+
+    /// Select every row of the view, hidden rows keep their state.
+    pub fn select_all(&mut self) {
+        let rows = self.ui.rows.lock().unwrap();
+        let mut selected = self.ui.selected.lock().unwrap();
+        selected.extend(self.view.iter().map(|&i| rows[i].key()));
+    }
+
+    pub fn invert_selected(&mut self) {
+        let rows = self.ui.rows.lock().unwrap();
+        let mut selected = self.ui.selected.lock().unwrap();
+        for &i in &self.view {
+            if !selected.remove(&rows[i].key()) {
+                selected.insert(rows[i].key());
+            }
+        }
+    }
+
+    /// Whether the selected rows can be cleared now, telling the user why not otherwise.
+    fn can_clear(&mut self) -> bool {
+        if self.clear_requested {
+            false
+        } else if !self.ui.status.is_done() {
+            self.notify("Scan is still running, wait for it to finish before clearing");
+            false
+        } else if self.ui.selected.lock().unwrap().is_empty() {
+            self.notify("Nothing selected, (Space) to select a row");
+            false
+        } else {
+            true
+        }
+    }
+
+    /// Open the confirmation dialog for the selection, or clear straight away with `--force`.
+    fn request_clear(&mut self) {
+        if !self.can_clear() {
+            return;
+        }
+        let confirm = {
+            let rows = self.ui.rows.lock().unwrap();
+            let selected = self.ui.selected.lock().unwrap();
+            Confirm::new(
+                &rows
+                    .iter()
+                    .filter(|row| selected.contains(&row.key()))
+                    .collect::<Vec<_>>(),
+                &self.ui.clean,
+            )
+        };
+        if self.ui.force {
+            self.send_clear(confirm.all());
+        } else {
+            self.confirm = Some(confirm);
+        }
+    }
+
+    fn confirm_key(&mut self, code: KeyCode) {
+        let Some(confirm) = self.confirm.as_mut() else {
+            return;
+        };
+        match confirm.key(code) {
+            Answer::Pending => {}
+            Answer::Cancel => self.confirm = None,
+            Answer::Done(confirmed) => {
+                self.confirm = None;
+                if confirmed.is_empty() {
+                    self.notify("Nothing confirmed, no directory was removed");
+                } else {
+                    self.send_clear(confirmed);
+                }
+            }
+        }
+    }
+
+    fn send_clear(&mut self, confirmed: HashSet<RowKey>) {
+        if self.ui.request.send(confirmed).is_ok() {
+            self.clear_requested = true;
+        }
+    }
+
+    /// Quitting in the middle of a clear would leave half removed directories.
+    fn can_quit(&mut self) -> bool {
+        if self.clear_requested && !self.ui.clear.is_done() {
+            self.notify("Clearing, wait for it to finish before quitting");
+            false
+        } else {
+            true
+        }
+    }
+
+    /// Follow a route `router` reported, eg: `/projects` opens the row under the cursor.
+    fn navigate(&mut self, path: &str, rtype: RouteType) {
+        match (path, rtype) {
+            ("/projects", RouteType::Push) => {
+                let rows = self.ui.rows.lock().unwrap();
+                let Some(row) = self
+                    .state
+                    .selected()
+                    .and_then(|i| self.view.get(i))
+                    .and_then(|&i| rows.get(i))
+                else {
+                    return;
+                };
+                let route = Route::new(RouteType::Pop, "detail", &row.project);
+                self.detail = Some(Detail::new(row, &self.ui.clean));
+                drop(rows);
+                self.router.push(vec![route], 0);
+            }
+            (_, RouteType::Pop) => {
+                self.router.pop();
+                self.detail = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// Handle a key press, `Some` exit code once the TUI should close.
+    fn on_key(&mut self, code: KeyCode) -> Option<usize> {
+        use KeyCode::*;
+        if self.confirm.is_some() {
+            self.confirm_key(code);
+            return None;
+        }
+        if self.filter.editing {
+            self.filter.key(code);
+            return None;
+        }
+        if code == Char('M') {
+            self.toggle_mouse();
+            return None;
+        }
+        let event = Event::Key(code.into());
+        match self.router.event(&event) {
+            EventType::Click(path, rtype) => {
+                self.navigate(&path, rtype);
+                return None;
+            }
+            EventType::Switch(path) => {
+                self.switch(&path);
+                return None;
+            }
+            EventType::Inner => {}
+        }
+        if self.detail.is_some() {
+            match code {
+                Char('q') | Esc if self.can_quit() => return Some(1),
+                Char('j') | Down => self.detail.as_mut().unwrap().next(),
+                Char('k') | Up => self.detail.as_mut().unwrap().previous(),
+                Char('t') => self.next_color(),
+                _ => {}
+            }
+            return None;
+        }
+        if self.router.current_tab().name != "projects" {
+            match code {
+                Char('q') | Esc if self.can_quit() => return Some(1),
+                Char('j') | Down => {
+                    let i = self.tab_state.selected().map_or(0, |i| i + 1);
+                    self.tab_state.select(Some(i));
+                }
+                Char('k') | Up => {
+                    let i = self.tab_state.selected().map_or(0, |i| i.saturating_sub(1));
+                    self.tab_state.select(Some(i));
+                }
+                Char('t') => self.next_color(),
+                _ => {}
+            }
+            return None;
+        }
+        match code {
+            Char('q') | Esc if self.can_quit() => return Some(1),
+            Char('y') | Enter => self.request_clear(),
+            // the selection is what is being cleared, keep it still
+            Char(' ' | 'a' | 'i') if self.clear_requested => {}
+            Char(' ') => self.toggle_selected(),
+            Char('a') => self.select_all(),
+            Char('i') => self.invert_selected(),
+            Char('/') => self.filter.editing = true,
+            Char('c') => self.filter.next_cate(),
+            Char('m') => self.filter.next_min_size(),
+            Char('1') => self.sort_by(SortKey::Project),
+            Char('2') => self.sort_by(SortKey::Cate),
+            Char('3') => self.sort_by(SortKey::Size),
+            Char('4') => self.sort_by(SortKey::Path),
+            Char('j') | Down => self.next(),
+            Char('k') | Up => self.previous(),
+            Char('t') => self.next_color(),
+            Char('T') => self.previous_color(),
+            _ => {}
+        }
+        None
+    }
+
+    /// Clicks select rows, sort by a header or press a footer action, the wheel moves the cursor.
+    fn on_mouse(&mut self, mouse: MouseEvent) -> Option<usize> {
+        let (x, y) = (mouse.column, mouse.row);
+        let hit = |area: &Rect| area.contains(Position { x, y });
+        match mouse.kind {
+            MouseEventKind::ScrollDown => self.on_key(KeyCode::Down),
+            MouseEventKind::ScrollUp => self.on_key(KeyCode::Up),
+            MouseEventKind::Down(MouseButton::Left) => {
+                if let Some(&(_, code)) = self.footer_hits.iter().find(|(area, _)| hit(area)) {
+                    return self.on_key(code);
+                }
+                let on_projects = self.confirm.is_none()
+                    && self.detail.is_none()
+                    && self.router.current_tab().name == "projects";
+                if !on_projects || !hit(&self.table_area) {
+                    return None;
+                }
+                if y == self.table_area.y {
+                    let [_, columns] =
+                        Layout::horizontal([Constraint::Length(BAR_WIDTH), Constraint::Fill(0)])
+                            .areas(self.table_area);
+                    let column = Layout::horizontal(COLUMNS)
+                        .spacing(1)
+                        .split(columns)
+                        .iter()
+                        .position(hit);
+                    if let Some(&key) = column.and_then(|i| SortKey::ALL.get(i.checked_sub(1)?)) {
+                        self.sort_by(key);
+                    }
+                } else {
+                    let i =
+                        ((y - self.table_area.y - 1) / ROW_HEIGHT) as usize + self.state.offset();
+                    if i < self.view.len() {
+                        self.select(i);
+                        if !self.clear_requested {
+                            self.toggle_selected();
+                        }
+                    }
+                }
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn toggle_mouse(&mut self) {
+        self.mouse = !self.mouse;
+        let result = if self.mouse {
+            execute!(io::stdout(), EnableMouseCapture)
+        } else {
+            execute!(io::stdout(), DisableMouseCapture)
+        };
+        match result {
+            Ok(()) if self.mouse => self.notify("Mouse on"),
+            Ok(()) => self.notify("Mouse off, the terminal selects text, (M) to turn it back on"),
+            Err(err) => self.notify(&format!("Toggle mouse Error: {err}")),
+        }
+    }
+
+    /// Load what the tab at `path` shows, it is drawn from App state afterwards.
+    fn switch(&mut self, path: &str) {
+        self.tab_state.select(Some(0));
+        match path {
+            "/caches" if self.caches.is_none() => self.caches = Some(Caches::new()),
+            // reread, this run may have removed something meanwhile
+            "/history" => match journal::read() {
+                Ok(entries) => self.history = entries,
+                Err(err) => self.notify(&format!("Read journal Error: {err}")),
+            },
+            _ => {}
+        }
+    }
+
+    fn notify(&mut self, notice: &str) {
+        self.notice = Some((notice.to_string(), Instant::now()));
     }
 
     pub fn next_color(&mut self) {
@@ -181,19 +662,18 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> io::Result<u
             .unwrap_or_else(|| Duration::from_secs(0));
 
         if crossterm::event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    use KeyCode::*;
-                    match key.code {
-                        Char('q') | Esc => return Ok(1),
-                        Char('y') | Enter => return Ok(0),
-                        Char('j') | Down => app.next(),
-                        Char('k') | Up => app.previous(),
-                        Char('l') | Right => app.next_color(),
-                        Char('h') | Left => app.previous_color(),
-                        _ => {}
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    if let Some(code) = app.on_key(key.code) {
+                        return Ok(code);
                     }
                 }
+                Event::Mouse(mouse) => {
+                    if let Some(code) = app.on_mouse(mouse) {
+                        return Ok(code);
+                    }
+                }
+                _ => {}
             }
         }
 
@@ -215,31 +695,107 @@ fn ui(f: &mut Frame, app: &mut App) {
 
     app.set_colors();
 
+    app.refresh_view();
+
     render_header(f, app, rects[0]);
 
-    render_table(f, app, rects[1]);
+    if let Some(detail) = app.detail.as_mut() {
+        detail.render(f, &app.colors, &app.router.breadcrumb(), rects[1]);
+    } else {
+        match app.router.current_tab().name.as_str() {
+            "categories" => tabs::render_categories(
+                f,
+                &app.colors,
+                &app.ui.rows.lock().unwrap(),
+                &app.ui.selected.lock().unwrap(),
+                &mut app.tab_state,
+                rects[1],
+            ),
+            "caches" => {
+                if let Some(caches) = &app.caches {
+                    caches.render(f, &app.colors, &mut app.tab_state, rects[1]);
+                }
+            }
+            "history" => {
+                tabs::render_history(f, &app.colors, &app.history, &mut app.tab_state, rects[1])
+            }
+            _ => {
+                render_table(f, app, rects[1]);
 
-    render_scrollbar(f, app, rects[1]);
+                render_scrollbar(f, app, rects[1]);
+            }
+        }
+    }
 
     render_table_total(f, app, rects[2]);
 
-    render_footer(f, app, rects[3]);
+    if app.clear_requested {
+        render_clear_gauge(f, app, rects[3]);
+    } else {
+        render_footer(f, app, rects[3]);
+    }
+
+    if let Some(confirm) = &app.confirm {
+        confirm.render(f, &app.colors, rects[1]);
+    }
+
+    if app.ui.clear.is_done() {
+        render_clear_summary(f, app, rects[1]);
+    }
 }
 
 fn render_header(f: &mut Frame, app: &App, area: Rect) {
-    let header = Paragraph::new("  🚀 Rmdev (https://github.com/WumaCoder/rmdev 🌟)")
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::new().fg(app.colors.footer_border_color))
         .style(
             Style::new()
                 .fg(app.colors.header_fg)
                 .bg(app.colors.header_bg),
-        )
-        // .alignment(Alignment::Center)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_style(Style::new().fg(app.colors.footer_border_color)),
         );
-    f.render_widget(header, area);
+    let block = block.title(TITLE.trim());
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let [tabs_area, status_area] =
+        Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)]).areas(inner);
+    app.router.render(
+        f,
+        tabs_area,
+        Style::new()
+            .fg(app.colors.selected_style_fg)
+            .add_modifier(Modifier::REVERSED),
+    );
+
+    let status = Paragraph::new(scan_status_line(&app.ui.status, status_area.width as usize))
+        .right_aligned();
+    f.render_widget(status, status_area);
+}
+
+fn scan_status_line(status: &ScanStatus, width: usize) -> String {
+    let elapsed = status.elapsed().as_secs();
+    let elapsed = format!("{:02}:{:02}", elapsed / 60, elapsed % 60);
+    if status.is_done() {
+        return format!("✔ scanned {} dirs in {elapsed} ", status.visited());
+    }
+
+    let spinner = SPINNER[(status.elapsed().as_millis() / 100) as usize % SPINNER.len()];
+    let line = format!("{spinner} scanning {} dirs {elapsed}  ", status.visited());
+    // keep the end of the current path, it is the part that changes
+    let current = status.current().to_string_lossy().to_string();
+    let room = width.saturating_sub(line.chars().count() + 2);
+    let count = current.chars().count();
+    let current = if count > room {
+        let tail = current.chars().skip(count - room + 1).collect::<String>();
+        if room > 0 {
+            format!("…{tail}")
+        } else {
+            tail
+        }
+    } else {
+        current
+    };
+    format!("{line}{current} ")
 }
 
 fn render_table(f: &mut Frame, app: &mut App, area: Rect) {
@@ -250,57 +806,73 @@ fn render_table(f: &mut Frame, app: &mut App, area: Rect) {
         .add_modifier(Modifier::REVERSED)
         .fg(app.colors.selected_style_fg);
 
-    let header = ScanRow::ref_head()
-        .into_iter()
+    let arrow = if app.descending { "▼" } else { "▲" };
+    let header = std::iter::once(String::new())
+        .chain(
+            ScanRow::ref_head()
+                .into_iter()
+                .enumerate()
+                .map(|(i, head)| {
+                    if i == app.sort.column() {
+                        format!("{head} {arrow}")
+                    } else {
+                        head.to_string()
+                    }
+                }),
+        )
+        .chain(["State".to_string()])
         .map(Cell::from)
         .collect::<Row>()
         .style(header_style)
         .height(1);
     let scan_rows = app.ui.rows.lock().unwrap();
-    let rows = scan_rows.iter().enumerate().map(|(i, scan_row)| {
+    let selected = app.ui.selected.lock().unwrap();
+    let rows = app.view.iter().enumerate().map(|(i, &row)| {
+        let scan_row = &scan_rows[row];
         let color = match i % 2 {
             0 => app.colors.normal_row_color,
             _ => app.colors.alt_row_color,
         };
+        let checkbox = if selected.contains(&scan_row.key()) {
+            "[x]"
+        } else {
+            "[ ]"
+        };
         let item = scan_row.ref_data();
-        item.into_iter()
+        let state = app.ui.clear.row(&scan_row.key());
+        let fg = match state {
+            Some(RowState::Failed(..)) => tailwind::RED.c400,
+            Some(RowState::Removed(_)) => tailwind::SLATE.c500,
+            _ => app.colors.row_fg,
+        };
+        std::iter::once(checkbox.to_string())
+            .chain(item)
+            .chain([state.as_ref().map(row_state_text).unwrap_or_default()])
             .map(|content| Cell::from(Text::from(format!("\n{content}\n"))))
             .collect::<Row>()
-            .style(Style::new().fg(app.colors.row_fg).bg(color))
-            .height(3)
+            .style(Style::new().fg(fg).bg(color))
+            .height(ROW_HEIGHT)
     });
     let bar = " █ ";
-    let t = Table::new(
-        rows,
-        [
-            Constraint::Percentage(25 as u16),
-            Constraint::Max(10 as u16),
-            Constraint::Max(10 as u16),
-            Constraint::Min(10 as u16),
-        ], // app.longest_item_lens
-           //     .iter()
-           //     .map(|w| Constraint::Min(*w as u16)),
-    )
-    .header(header)
-    .highlight_style(selected_style)
-    .highlight_symbol(Text::from(vec!["".into(), bar.into(), "".into()]))
-    .bg(app.colors.buffer_bg)
-    .highlight_spacing(HighlightSpacing::Always);
+    let t = Table::new(rows, COLUMNS)
+        .header(header)
+        .highlight_style(selected_style)
+        .highlight_symbol(Text::from(vec!["".into(), bar.into(), "".into()]))
+        .bg(app.colors.buffer_bg)
+        .highlight_spacing(HighlightSpacing::Always);
+    drop((scan_rows, selected));
+    app.table_area = area;
     f.render_stateful_widget(t, area, &mut app.state);
 }
 
-fn constraint_len_calculator(items: Arc<Mutex<Vec<ScanRow>>>) -> Vec<usize> {
-    let mut widths = ScanRow::ref_head().map(UnicodeWidthStr::width);
-
-    for (_i, row) in items.lock().unwrap().iter().enumerate() {
-        let row_widths = row.ref_data().map(|s| UnicodeWidthStr::width(s.as_str()));
-        for (_j, (width, row_width)) in widths.iter_mut().zip(row_widths).enumerate() {
-            if *width < row_width {
-                *width = row_width;
-            }
-        }
+fn row_state_text(state: &RowState) -> String {
+    match state {
+        RowState::Pending => "pending".to_string(),
+        RowState::Deleting => "deleting…".to_string(),
+        RowState::Removed(size) => format!("✔ freed {}", format_size(*size)),
+        RowState::Failed(_, err) => format!("✘ {err}"),
+        RowState::Planned(size) => format!("would free {}", format_size(*size)),
     }
-    widths.to_vec()
 }
 
 fn render_scrollbar(f: &mut Frame, app: &mut App, area: Rect) {
@@ -318,11 +890,35 @@ fn render_scrollbar(f: &mut Frame, app: &mut App, area: Rect) {
 }
 
 fn render_table_total(f: &mut Frame, app: &App, area: Rect) {
-    let items = app.ui.rows.lock().unwrap();
+    let rows = app.ui.rows.lock().unwrap();
+    let selected = app.ui.selected.lock().unwrap();
+    // only what the filter shows
+    let items = app.view.iter().map(|&i| &rows[i]).collect::<Vec<_>>();
+    // but all of the selection, a clear takes the rows the filter hides too
+    let selected_rows = rows.iter().filter(|r| selected.contains(&r.key()));
+    let hidden = selected_rows.clone().count()
+        - items.iter().filter(|r| selected.contains(&r.key())).count();
+    let hidden = if hidden > 0 {
+        format!(", {hidden} hidden")
+    } else {
+        String::new()
+    };
+    let filter = if app.filter.is_active() {
+        format!(
+            "FILTER: {} ({}/{})  ",
+            app.filter.describe(),
+            items.len(),
+            rows.len()
+        )
+    } else {
+        String::new()
+    };
     let info_footer = Paragraph::new(Line::from(format!(
-        "ROWS: {}  TOTAL: {:.2}GB   ",
+        "{filter}ROWS: {}  SELECTED: {} ({}{hidden})  TOTAL: {}   ",
         items.len(),
-        (items.iter().map(|r| r.size).sum::<u64>() as f64) / 1024.0 / 1024.0 / 1024.0
+        selected_rows.clone().count(),
+        format_size(selected_rows.map(|r| r.size).sum::<u64>()),
+        format_size(items.iter().map(|r| r.size).sum::<u64>())
     )))
     .style(Style::new().fg(app.colors.row_fg).bg(app.colors.buffer_bg))
     .right_aligned()
@@ -335,15 +931,173 @@ fn render_table_total(f: &mut Frame, app: &App, area: Rect) {
     f.render_widget(info_footer, area);
 }
 
-fn render_footer(f: &mut Frame, app: &App, area: Rect) {
-    let info_footer = Paragraph::new(Line::from(format!("{INFO_TEXT}",)))
+fn render_footer(f: &mut Frame, app: &mut App, area: Rect) {
+    let block = Block::bordered()
+        .border_type(BorderType::Double)
+        .borders(Borders::TOP | Borders::BOTTOM)
+        .border_style(Style::new().fg(app.colors.footer_border_color));
+    let inner = block.inner(area);
+    app.footer_hits.clear();
+
+    let notice = app
+        .notice
+        .as_ref()
+        .filter(|(_, at)| at.elapsed() < NOTICE_TIME);
+    let line = match notice {
+        _ if app.filter.editing => Line::from(app.filter.prompt()).fg(app.colors.selected_style_fg),
+        Some((notice, _)) => Line::from(notice.as_str()).fg(app.colors.selected_style_fg),
+        None => {
+            let actions = if app.detail.is_some() {
+                DETAIL_ACTIONS
+            } else if app.router.current_tab().name != "projects" {
+                TAB_ACTIONS
+            } else {
+                INFO_ACTIONS
+            };
+            let label_width = |label: &str| Span::from(label).width() as u16;
+            let width = actions
+                .iter()
+                .map(|(label, _)| label_width(label) + 3)
+                .sum::<u16>()
+                - 3;
+            // the line is centered, so is where each action lands
+            let mut x = inner.x + inner.width.saturating_sub(width) / 2;
+            let mut spans = Vec::new();
+            for (i, (label, code)) in actions.iter().enumerate() {
+                if i > 0 {
+                    spans.push(Span::from(" | "));
+                    x += 3;
+                }
+                if let Some(code) = code {
+                    app.footer_hits
+                        .push((Rect::new(x, inner.y, label_width(label), 1), *code));
+                }
+                spans.push(Span::from(*label));
+                x += label_width(label);
+            }
+            Line::from(spans)
+        }
+    };
+    let info_footer = Paragraph::new(line)
         .style(Style::new().fg(app.colors.row_fg).bg(app.colors.buffer_bg))
         .centered()
+        .block(block);
+    f.render_widget(info_footer, area);
+}
+
+fn render_clear_gauge(f: &mut Frame, app: &App, area: Rect) {
+    let progress = &app.ui.clear.progress;
+    let (done, total) = progress.jobs();
+    let label = if !app.ui.clear.is_running() && !app.ui.clear.is_done() {
+        "preparing…".to_string()
+    } else {
+        format!(
+            "{done}/{total} dirs  {} files  {}  {}",
+            progress.files(),
+            format_size(progress.bytes()),
+            format_rate(progress.bytes_per_sec())
+        )
+    };
+    let ratio = if app.ui.clear.is_done() {
+        1.0
+    } else {
+        progress.ratio()
+    };
+    let gauge = Gauge::default()
         .block(
             Block::bordered()
                 .border_type(BorderType::Double)
                 .borders(Borders::TOP | Borders::BOTTOM)
                 .border_style(Style::new().fg(app.colors.footer_border_color)),
+        )
+        .gauge_style(
+            Style::new()
+                .fg(app.colors.selected_style_fg)
+                .bg(app.colors.buffer_bg),
+        )
+        .ratio(ratio.clamp(0.0, 1.0))
+        .label(label);
+    f.render_widget(gauge, area);
+}
+
+fn render_clear_summary(f: &mut Frame, app: &App, area: Rect) {
+    let states = app.ui.clear.rows();
+    let planned = states
+        .iter()
+        .filter_map(|(_, state)| match state {
+            RowState::Planned(size) => Some(*size),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let removed = states
+        .iter()
+        .filter(|(_, state)| matches!(state, RowState::Removed(_)))
+        .count();
+    let freed = states
+        .iter()
+        .map(|(_, state)| match state {
+            RowState::Removed(size) | RowState::Failed(size, _) => *size,
+            _ => 0,
+        })
+        .sum::<u64>();
+    let failed = states
+        .iter()
+        .filter_map(|((path, cate), state)| match state {
+            RowState::Failed(_, err) => Some(format!("  {path:?} ({cate}): {err}")),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let mut lines = if app.ui.dry_run {
+        vec![
+            Line::from(format!(
+                "Would clear {} projects, {} in total.",
+                planned.len(),
+                format_size(planned.iter().sum())
+            )),
+            Line::from("Dry run, nothing was removed."),
+        ]
+    } else {
+        vec![
+            Line::from(format!(
+                "Cleared {removed} projects, {} failed.",
+                failed.len()
+            )),
+            Line::from(format!("Freed {}.", format_size(freed))),
+        ]
+    };
+    if let Some(err) = app.ui.clear.error() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(format!("Clear stopped, Error: {err}")).fg(tailwind::RED.c400));
+    }
+    if !failed.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from("Errors:").fg(tailwind::RED.c400));
+        lines.extend(failed.into_iter().map(Line::from));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from("(Esc) quit | (↑/↓) review rows"));
+
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let width = (area.width * 3 / 5).max(40).min(area.width);
+    let popup = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+    let summary = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .style(Style::new().fg(app.colors.row_fg).bg(app.colors.buffer_bg))
+        .block(
+            Block::bordered()
+                .title(if app.ui.dry_run {
+                    " Dry run finished "
+                } else {
+                    " Clear finished "
+                })
+                .border_style(Style::new().fg(app.colors.footer_border_color)),
         );
-    f.render_widget(info_footer, area);
+    f.render_widget(Clear, popup);
+    f.render_widget(summary, popup);
 }
@@ -0,0 +1,208 @@
+use std::{collections::HashSet, path::PathBuf};
+
+use ratatui::{crossterm::event::KeyCode, prelude::*, widgets::*};
+
+use super::TableColors;
+use crate::{
+    cargo::Clean,
+    command::clear::{find_artifacts, RowKey, ScanRow},
+    size::format_size,
+};
+
+/// A selected row and the directories clearing it removes.
+struct Item {
+    key: RowKey,
+    project: String,
+    cate: String,
+    size: u64,
+    artifacts: Vec<PathBuf>,
+}
+
+enum Step {
+    /// everything at once, the first page of the dialog
+    All,
+    /// index into `cates`
+    Category(usize),
+    /// index into `items`
+    Row(usize),
+}
+
+pub(super) enum Answer {
+    Pending,
+    /// the confirmed rows, the rest of the selection is skipped
+    Done(HashSet<RowKey>),
+    Cancel,
+}
+
+/// The modal asked before clearing the selected rows.
+pub(super) struct Confirm {
+    items: Vec<Item>,
+    cates: Vec<String>,
+    step: Step,
+    confirmed: HashSet<RowKey>,
+}
+
+impl Confirm {
+    pub(super) fn new(rows: &[&ScanRow], clean: &Clean) -> Self {
+        let items = rows
+            .iter()
+            .map(|row| Item {
+                key: row.key(),
+                project: row.project.clone(),
+                cate: row.cate.ident(),
+                size: row.size,
+                artifacts: find_artifacts(&row.path, &row.cate, clean),
+            })
+            .collect::<Vec<_>>();
+        let mut cates = Vec::new();
+        for item in &items {
+            if !cates.contains(&item.cate) {
+                cates.push(item.cate.clone());
+            }
+        }
+        Self {
+            items,
+            cates,
+            step: Step::All,
+            confirmed: HashSet::new(),
+        }
+    }
+
+    /// Every selected row, for when `--force` skips the dialog.
+    pub(super) fn all(&self) -> HashSet<RowKey> {
+        self.items.iter().map(|item| item.key.clone()).collect()
+    }
+
+    pub(super) fn key(&mut self, code: KeyCode) -> Answer {
+        use KeyCode::*;
+        match (&self.step, code) {
+            (_, Esc | Char('q')) => Answer::Cancel,
+            (Step::All, Char('y') | Enter) => Answer::Done(self.all()),
+            (Step::All, Char('n')) => Answer::Cancel,
+            (Step::All, Char('c')) => {
+                self.step = Step::Category(0);
+                Answer::Pending
+            }
+            (Step::All, Char('s')) => {
+                self.step = Step::Row(0);
+                Answer::Pending
+            }
+            (&Step::Category(i), Char(answer @ ('y' | 'n'))) => {
+                if answer == 'y' {
+                    let cate = &self.cates[i];
+                    self.confirmed.extend(
+                        self.items
+                            .iter()
+                            .filter(|item| &item.cate == cate)
+                            .map(|item| item.key.clone()),
+                    );
+                }
+                self.advance(i + 1, self.cates.len(), Step::Category)
+            }
+            (&Step::Row(i), Char(answer @ ('y' | 'n'))) => {
+                if answer == 'y' {
+                    self.confirmed.insert(self.items[i].key.clone());
+                }
+                self.advance(i + 1, self.items.len(), Step::Row)
+            }
+            _ => Answer::Pending,
+        }
+    }
+
+    fn advance(&mut self, next: usize, len: usize, step: fn(usize) -> Step) -> Answer {
+        if next < len {
+            self.step = step(next);
+            Answer::Pending
+        } else {
+            Answer::Done(std::mem::take(&mut self.confirmed))
+        }
+    }
+
+    pub(super) fn render(&self, f: &mut Frame, colors: &TableColors, area: Rect) {
+        let (title, items, help) = match self.step {
+            Step::All => (
+                " Confirm clear ".to_string(),
+                self.items.iter().collect::<Vec<_>>(),
+                "(y) confirm all | (c) by category | (s) step through | (Esc) cancel",
+            ),
+            Step::Category(i) => (
+                format!(
+                    " Category {}/{}: {} ",
+                    i + 1,
+                    self.cates.len(),
+                    self.cates[i]
+                ),
+                self.items
+                    .iter()
+                    .filter(|item| item.cate == self.cates[i])
+                    .collect(),
+                "(y) remove this category | (n) keep it | (Esc) cancel",
+            ),
+            Step::Row(i) => (
+                format!(" Project {}/{} ", i + 1, self.items.len()),
+                vec![&self.items[i]],
+                "(y) remove this project | (n) keep it | (Esc) cancel",
+            ),
+        };
+
+        let width = (area.width * 4 / 5).max(40).min(area.width);
+        let height = (area.height * 4 / 5).max(10).min(area.height);
+        let popup = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+        let block = Block::bordered()
+            .title(title)
+            .border_style(Style::new().fg(colors.footer_border_color))
+            .style(Style::new().fg(colors.row_fg).bg(colors.buffer_bg));
+        let inner = block.inner(popup);
+        f.render_widget(Clear, popup);
+        f.render_widget(block, popup);
+
+        let [summary_area, paths_area, help_area] = Layout::vertical([
+            Constraint::Length(2),
+            Constraint::Min(1),
+            Constraint::Length(1),
+        ])
+        .areas(inner);
+
+        let dirs = items.iter().map(|item| item.artifacts.len()).sum::<usize>();
+        let size = items.iter().map(|item| item.size).sum::<u64>();
+        f.render_widget(
+            Paragraph::new(format!(
+                "Remove {dirs} directories of {} projects, {} in total:",
+                items.len(),
+                format_size(size)
+            )),
+            summary_area,
+        );
+
+        let mut lines = Vec::new();
+        for item in &items {
+            lines.push(Line::from(format!(
+                "{}  ({}, {})",
+                item.project,
+                item.cate,
+                format_size(item.size)
+            )));
+            lines.extend(
+                item.artifacts
+                    .iter()
+                    .map(|path| Line::from(format!("  {path:?}")).fg(colors.selected_style_fg)),
+            );
+        }
+        let room = paths_area.height as usize;
+        if room == 0 {
+            lines.clear();
+        } else if lines.len() > room {
+            let more = lines.len() - room + 1;
+            lines.truncate(room - 1);
+            lines.push(Line::from(format!("… {more} more lines")));
+        }
+        f.render_widget(Paragraph::new(lines), paths_area);
+
+        f.render_widget(Paragraph::new(help).centered(), help_area);
+    }
+}
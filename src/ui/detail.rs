@@ -0,0 +1,189 @@
+use std::{
+    cmp::Reverse,
+    fs,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::Instant,
+};
+
+use ratatui::{prelude::*, widgets::*};
+
+use super::{TableColors, SPINNER};
+use crate::{
+    cargo::{self, Clean},
+    command::clear::{find_artifacts, get_directory_size, ScanRow, Visited},
+    scan_category::Layout,
+    size::format_size,
+};
+
+/// How deep below an artifact directory subdirectories are listed.
+const DEPTH: usize = 2;
+
+const BAR_WIDTH: usize = 20;
+
+/// The largest directories inside the artifacts of one row, sized in the background,
+/// for Cargo along with the size of each part `--cargo` can remove.
+pub(super) struct Detail {
+    /// labels sorted largest first
+    entries: Arc<Mutex<Vec<(String, u64)>>>,
+    done: Arc<AtomicBool>,
+    started: Instant,
+    state: TableState,
+}
+
+impl Detail {
+    pub(super) fn new(row: &ScanRow, clean: &Clean) -> Self {
+        let entries = Arc::new(Mutex::new(Vec::new()));
+        let done = Arc::new(AtomicBool::new(false));
+        thread::spawn({
+            let (root, cate, clean) = (row.path.clone(), row.cate.clone(), clean.clone());
+            let entries = entries.clone();
+            let done = done.clone();
+            move || {
+                let push = |label: String, size: u64| {
+                    let mut entries = entries.lock().unwrap();
+                    entries.push((label, size));
+                    entries.sort_by_key(|entry| Reverse(entry.1));
+                };
+                let size_of = |paths: &[PathBuf]| -> u64 {
+                    paths
+                        .iter()
+                        .map(|path| get_directory_size(path, Visited::default()).unwrap_or(0))
+                        .sum()
+                };
+                let target = cargo::built_target_dir(&root, &cate)
+                    .filter(|_| cate.layout() == Layout::Cargo);
+                // what each `--cargo` part would free, besides the directories themselves
+                if let Some(target) = &target {
+                    let triples = cargo::triples(target)
+                        .into_iter()
+                        .filter_map(|dir| Some(dir.file_name()?.to_str()?.to_string()))
+                        .map(Clean::Triple);
+                    for part in Clean::PARTS.into_iter().chain(triples) {
+                        push(
+                            format!("--cargo {}", part.label()),
+                            size_of(&part.select(target)),
+                        );
+                    }
+                    for (label, paths) in cargo::sweep_parts(target, &clean) {
+                        push(format!("--cargo {label}"), size_of(&paths));
+                    }
+                }
+                let artifacts = match target {
+                    Some(target) => vec![target],
+                    None => find_artifacts(&root, &cate, &clean),
+                };
+                for artifact in artifacts {
+                    for dir in subdirectories(artifact) {
+                        let size = size_of(std::slice::from_ref(&dir));
+                        let label = match dir.strip_prefix(&root) {
+                            Ok(rel) if rel.as_os_str().is_empty() => ".".to_string(),
+                            Ok(rel) => rel.to_string_lossy().to_string(),
+                            Err(_) => dir.to_string_lossy().to_string(),
+                        };
+                        push(label, size);
+                    }
+                }
+                done.store(true, Ordering::Relaxed);
+            }
+        });
+        Self {
+            entries,
+            done,
+            started: Instant::now(),
+            state: TableState::default().with_selected(0),
+        }
+    }
+
+    pub(super) fn next(&mut self) {
+        let len = self.entries.lock().unwrap().len();
+        let i = self.state.selected().map_or(0, |i| i + 1);
+        self.state.select(Some(if i >= len { 0 } else { i }));
+    }
+
+    pub(super) fn previous(&mut self) {
+        let len = self.entries.lock().unwrap().len();
+        let i = match self.state.selected() {
+            Some(0) | None => len.saturating_sub(1),
+            Some(i) => i - 1,
+        };
+        self.state.select(Some(i));
+    }
+
+    pub(super) fn render(&mut self, f: &mut Frame, colors: &TableColors, title: &str, area: Rect) {
+        let entries = self.entries.lock().unwrap();
+        let largest = entries.first().map_or(0, |entry| entry.1).max(1);
+        let rows = entries.iter().enumerate().map(|(i, (label, size))| {
+            let share = *size as f64 / largest as f64;
+            let filled = (share * BAR_WIDTH as f64).round() as usize;
+            Row::new([
+                label.clone(),
+                format_size(*size),
+                format!("{}{}", "█".repeat(filled), "░".repeat(BAR_WIDTH - filled)),
+            ])
+            .style(Style::new().fg(colors.row_fg).bg(match i % 2 {
+                0 => colors.normal_row_color,
+                _ => colors.alt_row_color,
+            }))
+        });
+
+        let status = if self.done.load(Ordering::Relaxed) {
+            format!("{} entries", entries.len())
+        } else {
+            let tick = self.started.elapsed().as_millis() / 100;
+            format!(
+                "{} sizing {} entries",
+                SPINNER[tick as usize % SPINNER.len()],
+                entries.len()
+            )
+        };
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Min(20),
+                Constraint::Length(10),
+                Constraint::Length(BAR_WIDTH as u16),
+            ],
+        )
+        .header(
+            Row::new(["Directory", "Size", "Share"])
+                .style(Style::new().fg(colors.header_fg).bg(colors.header_bg)),
+        )
+        .block(
+            Block::bordered()
+                .title(format!(" {title} "))
+                .title_bottom(Line::from(format!(" {status} ")).right_aligned())
+                .border_style(Style::new().fg(colors.footer_border_color)),
+        )
+        .highlight_style(
+            Style::new()
+                .add_modifier(Modifier::REVERSED)
+                .fg(colors.selected_style_fg),
+        )
+        .bg(colors.buffer_bg);
+        f.render_stateful_widget(table, area, &mut self.state);
+    }
+}
+
+/// `artifact` and every directory up to `DEPTH` below it, without following symlinks.
+fn subdirectories(artifact: PathBuf) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    let mut stack = vec![(artifact, 0)];
+    while let Some((dir, depth)) = stack.pop() {
+        if depth < DEPTH {
+            if let Ok(read) = fs::read_dir(&dir) {
+                for entry in read.flatten() {
+                    if entry.file_type().is_ok_and(|t| t.is_dir()) {
+                        stack.push((entry.path(), depth + 1));
+                    }
+                }
+            }
+        }
+        dirs.push(dir);
+    }
+    dirs
+}
@@ -0,0 +1,94 @@
+use ratatui::crossterm::event::KeyCode;
+
+use crate::{command::clear::ScanRow, scan_category, size::format_size};
+
+/// Thresholds `m` cycles through, in bytes.
+const MIN_SIZES: [u64; 4] = [0, 10 << 20, 100 << 20, 1 << 30];
+
+/// Which rows the table shows, the totals and bulk selection follow it.
+#[derive(Debug, Default)]
+pub(super) struct Filter {
+    query: String,
+    /// index into `scan_category::strategy()`
+    cate: Option<usize>,
+    /// index into `MIN_SIZES`
+    min_size: usize,
+    /// the `/` bar has the keyboard
+    pub(super) editing: bool,
+}
+
+impl Filter {
+    pub(super) fn matches(&self, row: &ScanRow) -> bool {
+        let cate = match self.cate {
+            Some(i) => scan_category::strategy()
+                .get(i)
+                .is_some_and(|cate| cate.ident() == row.cate.ident()),
+            None => true,
+        };
+        cate && row.size >= MIN_SIZES[self.min_size]
+            && (fuzzy_match(&self.query, &row.project)
+                || fuzzy_match(&self.query, &row.path.to_string_lossy()))
+    }
+
+    pub(super) fn is_active(&self) -> bool {
+        !self.query.is_empty() || self.cate.is_some() || self.min_size > 0
+    }
+
+    /// Typing in the `/` bar, Enter keeps the query and Esc drops it.
+    pub(super) fn key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char(c) => self.query.push(c),
+            KeyCode::Backspace => {
+                self.query.pop();
+            }
+            KeyCode::Enter => self.editing = false,
+            KeyCode::Esc => {
+                self.query.clear();
+                self.editing = false;
+            }
+            _ => {}
+        }
+    }
+
+    pub(super) fn next_cate(&mut self) {
+        let count = scan_category::strategy().len();
+        self.cate = match self.cate {
+            None if count > 0 => Some(0),
+            Some(i) if i + 1 < count => Some(i + 1),
+            _ => None,
+        };
+    }
+
+    pub(super) fn next_min_size(&mut self) {
+        self.min_size = (self.min_size + 1) % MIN_SIZES.len();
+    }
+
+    /// The bar while typing, eg: `/tar▏`.
+    pub(super) fn prompt(&self) -> String {
+        format!("/{}▏", self.query)
+    }
+
+    /// The active filters, eg: `/tar cate:Cargo ≥100.00MiB`.
+    pub(super) fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.query.is_empty() {
+            parts.push(format!("/{}", self.query));
+        }
+        if let Some(cate) = self.cate.and_then(|i| scan_category::strategy().get(i)) {
+            parts.push(format!("cate:{}", cate.ident()));
+        }
+        if self.min_size > 0 {
+            parts.push(format!("≥{}", format_size(MIN_SIZES[self.min_size])));
+        }
+        parts.join(" ")
+    }
+}
+
+/// Whether the characters of `query` appear in `text` in order, ignoring case.
+fn fuzzy_match(query: &str, text: &str) -> bool {
+    let mut text = text.chars().flat_map(char::to_lowercase);
+    query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|q| text.any(|t| t == q))
+}
@@ -2,28 +2,20 @@ use ratatui::crossterm::event::KeyCode;
 
 use ratatui::crossterm::event::Event;
 
-use ratatui::style::Stylize;
-use ratatui::widgets::List;
-
-use ratatui::widgets::ListItem;
-
-use ratatui::widgets::Borders;
-
-use ratatui::widgets::Block;
+use ratatui::widgets::Tabs;
 
 use ratatui::layout::Rect;
 
 use ratatui::terminal::Frame;
 
-use ratatui::style::Color;
+use ratatui::style::Style;
 
-use ratatui::text::Span;
-
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum RouteType {
     Pop,
     Push,
-    Quit,
+    /// a screen of its own, nothing to go into
+    View,
 }
 
 #[derive(Debug, Clone)]
@@ -31,7 +23,6 @@ pub(crate) struct Route {
     pub(crate) rtype: RouteType,
     pub(crate) label: String,
     pub(crate) name: String,
-    pub(crate) span: Span<'static>,
 }
 
 impl Route {
@@ -39,18 +30,9 @@ impl Route {
         Self {
             rtype,
             label: label.to_string(),
-            span: match rtype {
-                RouteType::Push => Span::from(label.to_string()).fg(Color::Blue),
-                _ => Span::from(label.to_string()).fg(Color::Red),
-            },
             name: name.to_string(),
         }
     }
-
-    pub(crate) fn with_span(mut self, span: Span<'static>) -> Self {
-        self.span = span.content(self.label.clone());
-        self
-    }
 }
 
 #[derive(Debug)]
@@ -86,6 +68,16 @@ impl Router {
         }
     }
 
+    /// The labels from the root to the current tab, eg: `Projects › app`.
+    pub(crate) fn breadcrumb(&self) -> String {
+        self.tabs_stack
+            .iter()
+            .map(|v| v.1[v.0].label.as_str())
+            .chain([self.current_tab().label.as_str()])
+            .collect::<Vec<_>>()
+            .join(" › ")
+    }
+
     pub(crate) fn push(&mut self, tabs: Vec<Route>, active: usize) {
         self.tabs_stack
             .push((self.active_tab, self.active_tabs.drain(..).collect()));
@@ -100,67 +92,54 @@ impl Router {
         }
     }
 
-    pub(crate) fn render(&self, f: &mut Frame, area: Rect) {
-        let block = Block::new().borders(Borders::ALL).title("Mongobar");
-        let items: Vec<ListItem> = self
-            .active_tabs
-            .iter()
-            .enumerate()
-            .map(|(i, t)| {
-                if i == self.active_tab {
-                    ListItem::new(t.span.clone()).bg(Color::DarkGray)
-                } else {
-                    ListItem::new(t.span.clone())
-                }
-            })
-            .collect();
-        let list = List::new(items).block(block);
-
-        f.render_widget(list, area);
+    /// The tabs of the current level, or where the user is once something was pushed.
+    pub(crate) fn render(&self, f: &mut Frame, area: Rect, highlight: Style) {
+        let tabs = if self.tabs_stack.is_empty() {
+            Tabs::new(self.active_tabs.iter().map(|t| t.label.clone()))
+                .select(self.active_tab)
+                .highlight_style(highlight)
+        } else {
+            Tabs::new([self.breadcrumb()]).select(0)
+        };
+        f.render_widget(tabs.divider("|"), area);
     }
 
+    /// Tab moves between the tabs of the current level,
+    /// Right goes into a `Push` route and Left back out of a pushed one,
+    /// the caller decides what the path leads to.
     pub(crate) fn event(&mut self, event: &Event) -> EventType {
         if let Event::Key(key) = event {
-            if key.code == KeyCode::Char('q') {
-                return EventType::Quit;
-            }
-            if key.code == KeyCode::Up {
-                if self.active_tab > 0 {
-                    self.active_tab -= 1;
-                } else {
-                    self.active_tab = self.active_tabs.len() - 1;
+            let cp = self.current_path();
+            let ctab = self.current_tab();
+            let cptab = cp.clone() + "/" + ctab.name.as_str();
+            match key.code {
+                KeyCode::Tab | KeyCode::BackTab if self.active_tabs.len() > 1 => {
+                    let len = self.active_tabs.len();
+                    self.active_tab = if key.code == KeyCode::Tab {
+                        (self.active_tab + 1) % len
+                    } else {
+                        (self.active_tab + len - 1) % len
+                    };
+                    return EventType::Switch(cp + "/" + self.current_tab().name.as_str());
+                }
+                KeyCode::Right | KeyCode::Char('l') if ctab.rtype == RouteType::Push => {
+                    return EventType::Click(cptab, RouteType::Push);
                 }
-            } else if key.code == KeyCode::Down {
-                if self.active_tab < self.active_tabs.len() - 1 {
-                    self.active_tab += 1;
-                } else {
-                    self.active_tab = 0;
+                KeyCode::Left | KeyCode::Char('h') if !self.tabs_stack.is_empty() => {
+                    return EventType::Click(cptab, RouteType::Pop);
                 }
-            } else if key.code == KeyCode::Enter {
-                let cp = self.current_path();
-                let ctab = self.current_tab();
-                let cptab = cp + "/" + ctab.name.as_str();
-                return EventType::Click(cptab, ctab.rtype, key.code);
-            } else if key.code == KeyCode::Left {
-                let cp = self.current_path();
-                let ctab = self.current_tab();
-                let cptab = cp + "/" + ctab.name.as_str();
-                return EventType::Click(cptab, ctab.rtype, key.code);
-            } else if key.code == KeyCode::Right {
-                let cp = self.current_path();
-                let ctab = self.current_tab();
-                let cptab = cp + "/" + ctab.name.as_str();
-                return EventType::Click(cptab, ctab.rtype, key.code);
+                _ => {}
             }
         }
 
-        return EventType::Inner;
+        EventType::Inner
     }
 }
 
 #[derive(Debug, Clone)]
 pub(crate) enum EventType {
-    Quit,
-    Click(String, RouteType, KeyCode),
+    Click(String, RouteType),
+    /// another tab of the same level became active
+    Switch(String),
     Inner,
 }
@@ -0,0 +1,199 @@
+use std::{
+    collections::HashSet,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use ratatui::{prelude::*, widgets::*};
+
+use super::TableColors;
+use crate::{
+    command::clear::{get_directory_size, RowKey, ScanRow, Visited},
+    journal::JournalEntry,
+    paths,
+    size::format_size,
+};
+
+/// Caches shared by every project, relative to the home directory.
+const GLOBAL_CACHES: [&str; 4] = [
+    ".cargo/registry",
+    ".cargo/git",
+    ".npm/_cacache",
+    ".cache/yarn",
+];
+
+/// Keep the cursor on a row after the list shrank or it moved past the end.
+fn clamp(state: &mut TableState, len: usize) {
+    if state.selected().is_some_and(|i| i >= len) {
+        state.select(len.checked_sub(1));
+    }
+}
+
+fn table<'a>(
+    rows: impl IntoIterator<Item = Vec<String>>,
+    head: &'a [&'a str],
+    widths: &'a [Constraint],
+    colors: &TableColors,
+) -> Table<'a> {
+    let rows = rows.into_iter().enumerate().map(|(i, cells)| {
+        Row::new(cells).style(Style::new().fg(colors.row_fg).bg(match i % 2 {
+            0 => colors.normal_row_color,
+            _ => colors.alt_row_color,
+        }))
+    });
+    Table::new(rows, widths)
+        .header(
+            Row::new(head.iter().copied())
+                .style(Style::new().fg(colors.header_fg).bg(colors.header_bg)),
+        )
+        .highlight_style(
+            Style::new()
+                .add_modifier(Modifier::REVERSED)
+                .fg(colors.selected_style_fg),
+        )
+        .bg(colors.buffer_bg)
+}
+
+/// The scanned rows summed per category.
+pub(super) fn render_categories(
+    f: &mut Frame,
+    colors: &TableColors,
+    rows: &[ScanRow],
+    selected: &HashSet<RowKey>,
+    state: &mut TableState,
+    area: Rect,
+) {
+    let mut cates: Vec<(String, usize, u64, usize, u64)> = Vec::new();
+    for row in rows {
+        let ident = row.cate.ident();
+        let i = match cates.iter().position(|cate| cate.0 == ident) {
+            Some(i) => i,
+            None => {
+                cates.push((ident, 0, 0, 0, 0));
+                cates.len() - 1
+            }
+        };
+        cates[i].1 += 1;
+        cates[i].2 += row.size;
+        if selected.contains(&row.key()) {
+            cates[i].3 += 1;
+            cates[i].4 += row.size;
+        }
+    }
+    cates.sort_by_key(|cate| std::cmp::Reverse(cate.2));
+    clamp(state, cates.len());
+
+    let t = table(
+        cates
+            .into_iter()
+            .map(|(ident, count, size, selected, selected_size)| {
+                vec![
+                    ident,
+                    count.to_string(),
+                    format_size(size),
+                    format!("{selected} ({})", format_size(selected_size)),
+                ]
+            }),
+        &["Cate", "Projects", "Size", "Selected"],
+        &[
+            Constraint::Percentage(30),
+            Constraint::Max(10),
+            Constraint::Max(12),
+            Constraint::Min(10),
+        ],
+        colors,
+    );
+    f.render_stateful_widget(t, area, state);
+}
+
+/// A cache path and its size, or why there is none, `None` while sizing.
+type CacheEntry = (PathBuf, Option<String>);
+
+/// Sizes of `GLOBAL_CACHES`, measured in the background the first time the tab is shown.
+pub(super) struct Caches {
+    entries: Arc<Mutex<Vec<CacheEntry>>>,
+}
+
+impl Caches {
+    pub(super) fn new() -> Self {
+        let home = paths::home_dir().unwrap_or_default();
+        let entries = Arc::new(Mutex::new(
+            GLOBAL_CACHES
+                .iter()
+                .map(|cache| (home.join(cache), None))
+                .collect::<Vec<_>>(),
+        ));
+        thread::spawn({
+            let entries = entries.clone();
+            move || {
+                let len = entries.lock().unwrap().len();
+                for i in 0..len {
+                    let path = entries.lock().unwrap()[i].0.clone();
+                    let size = match get_directory_size(&path, Visited::default()) {
+                        Ok(size) => format_size(size),
+                        Err(_) if !path.exists() => "not found".to_string(),
+                        Err(err) => err.to_string(),
+                    };
+                    entries.lock().unwrap()[i].1 = Some(size);
+                }
+            }
+        });
+        Self { entries }
+    }
+
+    pub(super) fn render(
+        &self,
+        f: &mut Frame,
+        colors: &TableColors,
+        state: &mut TableState,
+        area: Rect,
+    ) {
+        let entries = self.entries.lock().unwrap();
+        clamp(state, entries.len());
+        let t = table(
+            entries.iter().map(|(path, size)| {
+                vec![
+                    path.to_string_lossy().to_string(),
+                    size.clone().unwrap_or_else(|| "sizing…".to_string()),
+                ]
+            }),
+            &["Cache", "Size"],
+            &[Constraint::Min(20), Constraint::Max(24)],
+            colors,
+        );
+        f.render_stateful_widget(t, area, state);
+    }
+}
+
+/// What earlier runs removed, newest first.
+pub(super) fn render_history(
+    f: &mut Frame,
+    colors: &TableColors,
+    entries: &[JournalEntry],
+    state: &mut TableState,
+    area: Rect,
+) {
+    clamp(state, entries.len());
+    let t = table(
+        entries.iter().rev().map(|entry| {
+            vec![
+                entry.timestamp.format("%Y-%m-%d %H:%M:%S").to_string(),
+                format!("{:?}", entry.result).to_lowercase(),
+                format_size(entry.size),
+                entry.cate.clone(),
+                format!("{:?}", entry.path),
+            ]
+        }),
+        &["Time", "Result", "Size", "Cate", "Path"],
+        &[
+            Constraint::Length(19),
+            Constraint::Length(11),
+            Constraint::Length(10),
+            Constraint::Max(10),
+            Constraint::Min(10),
+        ],
+        colors,
+    );
+    f.render_stateful_widget(t, area, state);
+}